@@ -0,0 +1,318 @@
+use std::path::PathBuf;
+use std::process::exit;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use servicepoint2::{
+    load_image, text_to_cp437, BitVec, Brightness, ByteGrid, Command,
+    CompressionCode, Connection, Dithering, Grid, ImageOptions, Origin, Packet,
+    PixelGrid, Resize, PIXEL_COUNT, TILE_HEIGHT, TILE_WIDTH,
+};
+
+#[derive(Parser, Debug)]
+#[command(name = "servicepoint")]
+struct Cli {
+    #[arg(short, long, global = true, default_value = "localhost:2342")]
+    destination: String,
+    /// compression for commands containing pixels
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_enum,
+        default_value_t = CompressionArg::Lzma
+    )]
+    compression: CompressionArg,
+    /// print the encoded packet as a hex dump instead of sending it.
+    /// The output can be read by `servicepoint-inspect`.
+    #[arg(short = 'n', long, global = true)]
+    dry_run: bool,
+    #[command(subcommand)]
+    command: CommandArg,
+}
+
+#[derive(Subcommand, Debug)]
+enum CommandArg {
+    /// turn all pixels off
+    Clear,
+    /// set the brightness of the whole screen
+    Brightness {
+        /// from 0 to 11
+        #[arg(value_parser = parse_brightness)]
+        value: Brightness,
+    },
+    /// set the brightness of a window of tiles
+    CharBrightness {
+        /// from 0 to 11
+        #[arg(value_parser = parse_brightness)]
+        value: Brightness,
+        #[command(flatten)]
+        window: TileWindow,
+    },
+    /// show text in the text layer, one argument per line
+    Text {
+        #[arg(required = true)]
+        lines: Vec<String>,
+        /// column of the first character in tiles
+        #[arg(short, long, default_value_t = 0)]
+        x: usize,
+        /// row of the first line in tiles
+        #[arg(short, long, default_value_t = 0)]
+        y: usize,
+    },
+    /// show a PNG, JPEG or GIF file scaled to the whole screen
+    Image {
+        file: PathBuf,
+        /// how the image is scaled to the screen
+        #[arg(short, long, value_enum, default_value_t = ResizeArg::Fit)]
+        resize: ResizeArg,
+        /// how gray pixels are turned into on and off
+        #[arg(long, value_enum, default_value_t = DitheringArg::FloydSteinberg)]
+        dithering: DitheringArg,
+        /// turn dark pixels on instead of bright ones
+        #[arg(short, long)]
+        invert: bool,
+    },
+    /// turn all pixels on, or combine them with all on using a bitwise operation
+    Fill {
+        /// turn the pixels off instead
+        #[arg(long)]
+        off: bool,
+        /// which bitmap command is used
+        #[arg(long, value_enum, default_value_t = FillMode::Window)]
+        mode: FillMode,
+    },
+    /// slowly turn off the display
+    FadeOut,
+    /// restart the display, which takes a while
+    HardReset {
+        /// confirm that the display should really be restarted
+        #[arg(long)]
+        yes: bool,
+    },
+    /// send a packet given as hex, e.g. "0002 0000 0000 0000 0000"
+    SendRaw {
+        /// whitespace and colons between the digits are ignored
+        #[arg(required = true)]
+        hex: Vec<String>,
+    },
+}
+
+/// A window of tiles, the whole screen by default
+#[derive(Args, Debug)]
+struct TileWindow {
+    #[arg(short, long, default_value_t = 0)]
+    x: usize,
+    #[arg(short, long, default_value_t = 0)]
+    y: usize,
+    #[arg(long, default_value_t = TILE_WIDTH)]
+    width: usize,
+    #[arg(long, default_value_t = TILE_HEIGHT)]
+    height: usize,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum CompressionArg {
+    Uncompressed,
+    Zlib,
+    Bzip2,
+    Lzma,
+    Zstd,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum ResizeArg {
+    /// scale to fit completely, keeping the aspect ratio
+    Fit,
+    /// scale to cover everything, cutting off the overlapping parts
+    Fill,
+    /// scale to exactly the screen size, ignoring the aspect ratio
+    Stretch,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum DitheringArg {
+    /// every pixel brighter than the threshold is on
+    None,
+    /// a regular 4x4 pattern, looks calm in animations
+    Ordered,
+    /// error diffusion, shows the most detail in still images
+    FloydSteinberg,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum FillMode {
+    /// `BitmapLinearWin`, replaces the pixels
+    Window,
+    /// `BitmapLinear`, replaces the pixels
+    Linear,
+    /// `BitmapLinearAnd`
+    And,
+    /// `BitmapLinearOr`
+    Or,
+    /// `BitmapLinearXor`
+    Xor,
+}
+
+/// example: `cargo run --bin servicepoint -- text "Hello," "CCCB"`
+fn main() {
+    env_logger::init();
+    let cli = Cli::parse();
+
+    let packet = match packet(cli.command, cli.compression.into()) {
+        Ok(packet) => packet,
+        Err(err) => {
+            eprintln!("{err}");
+            exit(1);
+        }
+    };
+
+    if cli.dry_run {
+        if let Ok(command) = Command::try_from(Packet::from(packet.as_ref())) {
+            println!("# {command}");
+        }
+        println!("# {packet}");
+        let bytes: Vec<u8> = packet.into();
+        let hex: Vec<String> =
+            bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+        println!("{}", hex.join(" "));
+        return;
+    }
+
+    let connection =
+        Connection::open(&cli.destination).expect("could not open connection");
+    connection.send(packet).expect("send failed");
+}
+
+fn packet(
+    command: CommandArg,
+    compression: CompressionCode,
+) -> Result<Packet, String> {
+    let command = match command {
+        CommandArg::Clear => Command::Clear,
+        CommandArg::Brightness { value } => Command::Brightness(value),
+        CommandArg::CharBrightness { value, window } => {
+            if window.x + window.width > TILE_WIDTH
+                || window.y + window.height > TILE_HEIGHT
+            {
+                return Err(String::from(
+                    "the window is outside of the screen",
+                ));
+            }
+            let mut grid = ByteGrid::new(window.width, window.height);
+            grid.fill(value.into());
+            Command::CharBrightness(Origin(window.x, window.y), grid)
+        }
+        CommandArg::Text { lines, x, y } => {
+            let grid = text_to_cp437(&lines.join("\n"))
+                .map_err(|err| err.to_string())?;
+            if x + grid.width() > TILE_WIDTH || y + grid.height() > TILE_HEIGHT
+            {
+                return Err(String::from("the text is outside of the screen"));
+            }
+            Command::Cp437Data(Origin(x, y), grid)
+        }
+        CommandArg::Image {
+            file,
+            resize,
+            dithering,
+            invert,
+        } => {
+            let options = ImageOptions {
+                resize: resize.into(),
+                dithering: dithering.into(),
+                invert,
+                ..ImageOptions::default()
+            };
+            let pixels = load_image(&file, &options).map_err(|err| {
+                format!("could not load {}: {err}", file.display())
+            })?;
+            Command::BitmapLinearWin(Origin(0, 0), pixels, compression)
+        }
+        CommandArg::Fill { off, mode } => {
+            let mut bits = BitVec::new(PIXEL_COUNT);
+            bits.fill(!off);
+            match mode {
+                FillMode::Window => {
+                    let mut pixels = PixelGrid::max_sized();
+                    pixels.fill(!off);
+                    Command::BitmapLinearWin(Origin(0, 0), pixels, compression)
+                }
+                FillMode::Linear => Command::BitmapLinear(0, bits, compression),
+                FillMode::And => Command::BitmapLinearAnd(0, bits, compression),
+                FillMode::Or => Command::BitmapLinearOr(0, bits, compression),
+                FillMode::Xor => Command::BitmapLinearXor(0, bits, compression),
+            }
+        }
+        CommandArg::FadeOut => Command::FadeOut,
+        CommandArg::HardReset { yes } => {
+            if !yes {
+                return Err(String::from(
+                    "hard-reset restarts the display, pass --yes to confirm",
+                ));
+            }
+            Command::HardReset
+        }
+        CommandArg::SendRaw { hex } => return decode_hex(&hex.concat()),
+    };
+    Ok(command.into())
+}
+
+fn decode_hex(hex: &str) -> Result<Packet, String> {
+    let digits: Vec<char> = hex
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && *c != ':')
+        .collect();
+    if digits.len() % 2 == 1 {
+        return Err(String::from("odd number of hex digits"));
+    }
+    let bytes = digits
+        .chunks(2)
+        .map(|pair| {
+            let pair: String = pair.iter().collect();
+            u8::from_str_radix(&pair, 16)
+                .map_err(|_| format!("invalid hex digits '{pair}'"))
+        })
+        .collect::<Result<Vec<u8>, String>>()?;
+    Packet::try_from(bytes.as_slice())
+        .map_err(|()| String::from("too short for a packet header"))
+}
+
+fn parse_brightness(value: &str) -> Result<Brightness, String> {
+    let value: u8 = value.parse().map_err(|err| format!("{err}"))?;
+    Brightness::new(value).ok_or_else(|| {
+        format!("has to be at most {}, got {value}", Brightness::MAX)
+    })
+}
+
+impl From<CompressionArg> for CompressionCode {
+    fn from(compression: CompressionArg) -> Self {
+        match compression {
+            CompressionArg::Uncompressed => CompressionCode::Uncompressed,
+            CompressionArg::Zlib => CompressionCode::Zlib,
+            CompressionArg::Bzip2 => CompressionCode::Bzip2,
+            CompressionArg::Lzma => CompressionCode::Lzma,
+            CompressionArg::Zstd => CompressionCode::Zstd,
+        }
+    }
+}
+
+impl From<ResizeArg> for Resize {
+    fn from(resize: ResizeArg) -> Self {
+        match resize {
+            ResizeArg::Fit => Resize::Fit,
+            ResizeArg::Fill => Resize::Fill,
+            ResizeArg::Stretch => Resize::Stretch,
+        }
+    }
+}
+
+impl From<DitheringArg> for Dithering {
+    fn from(dithering: DitheringArg) -> Self {
+        match dithering {
+            DitheringArg::None => Dithering::None,
+            DitheringArg::Ordered => Dithering::Ordered,
+            DitheringArg::FloydSteinberg => Dithering::FloydSteinberg,
+        }
+    }
+}
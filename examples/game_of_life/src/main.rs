@@ -1,5 +1,3 @@
-use std::thread;
-
 use clap::Parser;
 use rand::{distributions, Rng};
 
@@ -20,28 +18,24 @@ fn main() {
     let connection = Connection::open(&cli.destination).unwrap();
     let mut field = make_random_field(cli.probability);
 
-    loop {
-        connection
-            .send(
-                Command::BitmapLinearWin(
-                    Origin(0, 0),
-                    field.clone(),
-                    CompressionCode::Lzma,
-                )
-                .into(),
-            )
-            .expect("could not send");
-        thread::sleep(FRAME_PACING);
-        field = iteration(field);
-    }
+    let mut animator = Animator::new();
+    animator.set_compression(CompressionCode::Lzma);
+    let handle = animator.handle();
+    ctrlc::set_handler(move || handle.stop()).unwrap();
+
+    animator
+        .run(&connection, &mut field, |field, _| {
+            *field = iteration(field);
+        })
+        .expect("could not send");
 }
 
-fn iteration(field: PixelGrid) -> PixelGrid {
+fn iteration(field: &PixelGrid) -> PixelGrid {
     let mut next = field.clone();
     for x in 0..field.width() {
         for y in 0..field.height() {
             let old_state = field.get(x, y);
-            let neighbors = count_neighbors(&field, x as i32, y as i32);
+            let neighbors = count_neighbors(field, x as i32, y as i32);
 
             let new_state = matches!(
                 (old_state, neighbors),
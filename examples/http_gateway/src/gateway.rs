@@ -0,0 +1,399 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::Duration;
+
+use log::{info, warn};
+use serde::Deserialize;
+use serde_json::json;
+
+use servicepoint2::{
+    decode_frames, text_to_cp437, Brightness, BrightnessOutOfRange, Command,
+    CompressionCode, Connection, Grid, ImageOptions, Origin, Policy, Rejection,
+    PIXEL_HEIGHT, PIXEL_WIDTH, TILE_HEIGHT, TILE_SIZE, TILE_WIDTH,
+};
+
+use crate::pbm::{is_pbm, read_pbm};
+
+/// An HTTP request, independent of the server library
+pub struct Request<'a> {
+    pub source: IpAddr,
+    pub method: &'a str,
+    /// path including the query
+    pub url: &'a str,
+    pub content_type: Option<&'a str>,
+    pub body: &'a [u8],
+}
+
+/// The answer to a request, the body is JSON
+#[derive(Debug)]
+pub struct Reply {
+    pub status: u16,
+    pub body: String,
+    pub retry_after: Option<Duration>,
+}
+
+impl Reply {
+    fn sent(command: &Command) -> Self {
+        Self {
+            status: 200,
+            body: json!({ "sent": command.to_string() }).to_string(),
+            retry_after: None,
+        }
+    }
+
+    pub fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.into() }).to_string(),
+            retry_after: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BrightnessBody {
+    value: Brightness,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TextBody {
+    text: String,
+    #[serde(default)]
+    x: usize,
+    #[serde(default)]
+    y: usize,
+}
+
+/// Turns requests into commands and forwards them to the display.
+pub struct Gateway {
+    connection: Connection,
+    policy: Policy<IpAddr>,
+    compression: CompressionCode,
+}
+
+impl Gateway {
+    pub fn new(
+        connection: Connection,
+        policy: Policy<IpAddr>,
+        compression: CompressionCode,
+    ) -> Self {
+        Self {
+            connection,
+            policy,
+            compression,
+        }
+    }
+
+    pub fn handle(&mut self, request: &Request) -> Reply {
+        let (path, query) =
+            request.url.split_once('?').unwrap_or((request.url, ""));
+        let query: HashMap<&str, &str> = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+            .collect();
+
+        if request.method == "GET" && path == "/" {
+            return Reply {
+                status: 200,
+                body: json!({ "endpoints": ENDPOINTS }).to_string(),
+                retry_after: None,
+            };
+        }
+        if request.method != "POST" {
+            return Reply::error(405, "only POST is supported");
+        }
+
+        let is_json = request.content_type.is_some_and(|content_type| {
+            content_type.starts_with("application/json")
+        });
+        let command = match path {
+            "/clear" => Ok(Command::Clear),
+            "/brightness" => brightness(request.body, is_json),
+            "/text" => text(request.body, is_json, &query),
+            "/image" => self.image(request.body, &query),
+            "/command" if is_json => serde_json::from_slice(request.body)
+                .map_err(|err| err.to_string()),
+            "/command" => Err(String::from("commands have to be sent as JSON")),
+            _ => return Reply::error(404, format!("unknown endpoint {path}")),
+        };
+        let command = match command {
+            Ok(command) => command,
+            Err(message) => return Reply::error(400, message),
+        };
+
+        let command = match self.policy.check(request.source, command) {
+            Ok(command) => command,
+            Err(Rejection::TooManyPackets(retry_after)) => {
+                return Reply {
+                    retry_after: Some(retry_after),
+                    ..Reply::error(
+                        429,
+                        Rejection::TooManyPackets(retry_after).to_string(),
+                    )
+                };
+            }
+            Err(rejection) => return Reply::error(403, rejection.to_string()),
+        };
+
+        info!("{} sent {command}", request.source);
+        let reply = Reply::sent(&command);
+        match self.connection.send(command.into()) {
+            Ok(()) => reply,
+            Err(err) => {
+                warn!("could not forward to the display: {err}");
+                Reply::error(502, format!("could not reach the display: {err}"))
+            }
+        }
+    }
+
+    /// A PBM, PNG, JPEG or GIF file, placed at `?x=&y=` in pixels
+    fn image(
+        &self,
+        body: &[u8],
+        query: &HashMap<&str, &str>,
+    ) -> Result<Command, String> {
+        let x: usize = query_number(query, "x")?;
+        let y: usize = query_number(query, "y")?;
+        if x / TILE_SIZE * TILE_SIZE != x {
+            return Err(format!("x has to be a multiple of {TILE_SIZE}"));
+        }
+        if x >= PIXEL_WIDTH || y >= PIXEL_HEIGHT {
+            return Err(format!("({x}, {y}) is outside of the screen"));
+        }
+
+        let pixels = if is_pbm(body) {
+            read_pbm(body, PIXEL_WIDTH - x, PIXEL_HEIGHT - y)?
+        } else {
+            let options = ImageOptions {
+                width: PIXEL_WIDTH - x,
+                height: PIXEL_HEIGHT - y,
+                invert: query.get("invert") == Some(&"true"),
+                ..ImageOptions::default()
+            };
+            let frames =
+                decode_frames(body, &options).map_err(|err| err.to_string())?;
+            frames
+                .into_iter()
+                .next()
+                .ok_or("the image has no frames")?
+                .pixels
+        };
+        Ok(Command::BitmapLinearWin(
+            Origin(x, y),
+            pixels,
+            self.compression,
+        ))
+    }
+}
+
+const ENDPOINTS: [&str; 5] = [
+    "POST /clear",
+    "POST /brightness: a number from 0 to 11, or JSON {\"value\": 11}",
+    "POST /text?x=&y=: lines of text, or JSON {\"text\": \"\", \"x\": 0, \"y\": 0}",
+    "POST /image?x=&y=&invert=: a PBM, PNG, JPEG or GIF file",
+    "POST /command: any command as JSON",
+];
+
+fn brightness(body: &[u8], is_json: bool) -> Result<Command, String> {
+    if is_json {
+        let body: BrightnessBody =
+            serde_json::from_slice(body).map_err(|err| err.to_string())?;
+        return Ok(Command::Brightness(body.value));
+    }
+
+    let value: u8 = std::str::from_utf8(body)
+        .ok()
+        .and_then(|text| text.trim().parse().ok())
+        .ok_or("expected a number from 0 to 11")?;
+    let brightness = Brightness::new(value)
+        .ok_or_else(|| BrightnessOutOfRange(value).to_string())?;
+    Ok(Command::Brightness(brightness))
+}
+
+/// Text in the text layer, position in tiles
+fn text(
+    body: &[u8],
+    is_json: bool,
+    query: &HashMap<&str, &str>,
+) -> Result<Command, String> {
+    let body = if is_json {
+        serde_json::from_slice(body).map_err(|err| err.to_string())?
+    } else {
+        TextBody {
+            text: String::from_utf8(body.to_vec())
+                .map_err(|_| "the text is not valid UTF-8")?,
+            x: query_number(query, "x")?,
+            y: query_number(query, "y")?,
+        }
+    };
+
+    let grid = text_to_cp437(&body.text).map_err(|err| err.to_string())?;
+    if body.x.saturating_add(grid.width()) > TILE_WIDTH
+        || body.y.saturating_add(grid.height()) > TILE_HEIGHT
+    {
+        return Err(String::from("the text is outside of the screen"));
+    }
+    Ok(Command::Cp437Data(Origin(body.x, body.y), grid))
+}
+
+fn query_number(
+    query: &HashMap<&str, &str>,
+    name: &str,
+) -> Result<usize, String> {
+    match query.get(name) {
+        None => Ok(0),
+        Some(value) => value
+            .parse()
+            .map_err(|_| format!("{name} has to be a number, got '{value}'")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+    use std::time::Duration;
+
+    use servicepoint2::{
+        Area, Command, CompressionCode, Connection, Grid, Origin, Packet,
+        Policy,
+    };
+
+    use super::{Gateway, Request};
+
+    const CLIENT: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+    /// A gateway forwarding to a local socket instead of a display
+    fn gateway(policy: Policy<IpAddr>) -> (Gateway, UdpSocket) {
+        let display = UdpSocket::bind("127.0.0.1:0").unwrap();
+        display
+            .set_read_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+        let connection =
+            Connection::open(display.local_addr().unwrap()).unwrap();
+        let gateway =
+            Gateway::new(connection, policy, CompressionCode::Uncompressed);
+        (gateway, display)
+    }
+
+    fn post<'a>(
+        url: &'a str,
+        content_type: Option<&'a str>,
+        body: &'a [u8],
+    ) -> Request<'a> {
+        Request {
+            source: CLIENT,
+            method: "POST",
+            url,
+            content_type,
+            body,
+        }
+    }
+
+    fn received(display: &UdpSocket) -> Command {
+        let mut buffer = [0u8; 10000];
+        let size = display.recv(&mut buffer).unwrap();
+        let packet = Packet::try_from(&buffer[..size]).unwrap();
+        Command::try_from(packet).unwrap()
+    }
+
+    #[test]
+    fn forwards_commands() {
+        let (mut gateway, display) = gateway(Policy::new());
+
+        let reply = gateway.handle(&post("/clear", None, b""));
+        assert_eq!(reply.status, 200);
+        assert_eq!(received(&display), Command::Clear);
+
+        let reply = gateway.handle(&post("/brightness", None, b"5\n"));
+        assert_eq!(reply.status, 200);
+        assert!(
+            matches!(received(&display), Command::Brightness(b) if u8::from(b) == 5)
+        );
+
+        let json = Some("application/json");
+        let body = br#"{"text": "hi\nyou", "x": 2}"#;
+        assert_eq!(gateway.handle(&post("/text", json, body)).status, 200);
+        let Command::Cp437Data(Origin(2, 0), grid) = received(&display) else {
+            panic!("expected text");
+        };
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(2, 0), b' ');
+
+        let body = br#""FadeOut""#;
+        assert_eq!(gateway.handle(&post("/command", json, body)).status, 200);
+        assert_eq!(received(&display), Command::FadeOut);
+    }
+
+    #[test]
+    fn converts_pbm() {
+        let (mut gateway, display) = gateway(Policy::new());
+        let reply = gateway.handle(&post(
+            "/image?x=8&y=1",
+            None,
+            b"P1\n2 2\n1 0\n0 1\n",
+        ));
+        assert_eq!(reply.status, 200, "{}", reply.body);
+        let Command::BitmapLinearWin(Origin(8, 1), pixels, _) =
+            received(&display)
+        else {
+            panic!("expected pixels");
+        };
+        assert_eq!((pixels.width(), pixels.height()), (8, 2));
+        assert!(pixels.get(0, 0) && pixels.get(1, 1) && !pixels.get(1, 0));
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        let (mut gateway, _display) =
+            gateway(Policy::new().allow_area(Area::FULL_SCREEN));
+        let json = Some("application/json");
+
+        let reply = gateway.handle(&post("/brightness", None, b"12"));
+        assert_eq!(reply.status, 400);
+        assert!(reply.body.contains("higher than the maximum of 11"));
+
+        let reply =
+            gateway.handle(&post("/brightness", json, br#"{"value": 20}"#));
+        assert_eq!(reply.status, 400);
+        assert!(reply.body.contains("higher than the maximum of 11"));
+
+        let reply = gateway.handle(&post("/text", None, "€".as_bytes()));
+        assert_eq!(reply.status, 400);
+
+        let reply = gateway.handle(&post("/text?x=55", None, b"ab"));
+        assert_eq!(reply.status, 400, "{}", reply.body);
+        assert!(reply.body.contains("outside of the screen"));
+
+        let reply = gateway.handle(&post("/text?y=19", None, b"a\nb"));
+        assert_eq!(reply.status, 400, "{}", reply.body);
+
+        let text = "a".repeat(57);
+        let reply = gateway.handle(&post("/text", None, text.as_bytes()));
+        assert_eq!(reply.status, 400, "{}", reply.body);
+
+        let reply = gateway.handle(&post("/image?x=3", None, b"P1 1 1 1"));
+        assert_eq!(reply.status, 400);
+
+        let reply =
+            gateway.handle(&post("/image?x=8", None, b"P4 99999999 99999999 "));
+        assert_eq!(reply.status, 400);
+        assert!(reply.body.contains("bigger than 440x160"), "{}", reply.body);
+
+        assert_eq!(gateway.handle(&post("/unknown", None, b"")).status, 404);
+    }
+
+    #[test]
+    fn limits_rate() {
+        let (mut gateway, display) =
+            gateway(Policy::new().max_packets_per_second(1.0).unwrap());
+        assert_eq!(gateway.handle(&post("/clear", None, b"")).status, 200);
+        received(&display);
+
+        let reply = gateway.handle(&post("/clear", None, b""));
+        assert_eq!(reply.status, 429);
+        assert!(reply.retry_after.is_some());
+    }
+}
@@ -0,0 +1,111 @@
+use std::io::Read;
+use std::net::{IpAddr, Ipv4Addr};
+use std::process::exit;
+
+use clap::Parser;
+use log::{info, warn};
+use tiny_http::{Header, Response, Server};
+
+use servicepoint2::{
+    Area, Brightness, CommandKind, CompressionCode, Connection, Policy,
+};
+
+use crate::gateway::{Gateway, Reply, Request};
+
+mod gateway;
+mod pbm;
+
+/// Bigger uploads are rejected without being read completely
+const MAX_BODY_SIZE: u64 = 4 * 1024 * 1024;
+
+#[derive(Parser, Debug)]
+struct Cli {
+    /// address to accept HTTP requests on
+    #[arg(short, long, default_value = "127.0.0.1:8080")]
+    listen: String,
+    #[arg(short, long, default_value = "localhost:2342")]
+    destination: String,
+    /// maximum requests per second and client address
+    #[arg(long, default_value_t = 10.0)]
+    max_rate: f64,
+    /// brightness values are lowered to this
+    #[arg(long)]
+    max_brightness: Option<u8>,
+}
+
+/// example: `cargo run --bin servicepoint-http`, then
+/// `curl --data "Hello, CCCB" localhost:8080/text`
+fn main() {
+    env_logger::init();
+    let cli = Cli::parse();
+
+    let mut policy = Policy::new()
+        .deny(CommandKind::HardReset)
+        .deny(CommandKind::BitmapLegacy)
+        .allow_area(Area::FULL_SCREEN)
+        .max_packets_per_second(cli.max_rate)
+        .unwrap_or_else(|err| {
+            eprintln!("{err}");
+            exit(1);
+        });
+    if let Some(max_brightness) = cli.max_brightness {
+        let max_brightness = Brightness::saturating_from(max_brightness);
+        policy = policy.brightness_range(Brightness::MIN..=max_brightness);
+    }
+    let connection =
+        Connection::open(&cli.destination).expect("could not open connection");
+    let mut gateway = Gateway::new(connection, policy, CompressionCode::Lzma);
+
+    let server = Server::http(&cli.listen).expect("could not listen");
+    info!("listening on {}", cli.listen);
+    for mut request in server.incoming_requests() {
+        let mut body = vec![];
+        if let Err(err) = request
+            .as_reader()
+            .take(MAX_BODY_SIZE + 1)
+            .read_to_end(&mut body)
+        {
+            warn!("could not read request: {err}");
+            continue;
+        }
+
+        let content_type = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("Content-Type"))
+            .map(|header| header.value.to_string());
+        let reply = if body.len() as u64 > MAX_BODY_SIZE {
+            Reply::error(
+                413,
+                format!("the body is bigger than {MAX_BODY_SIZE} bytes"),
+            )
+        } else {
+            gateway.handle(&Request {
+                source: request
+                    .remote_addr()
+                    .map_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED), |addr| {
+                        addr.ip()
+                    }),
+                method: request.method().as_str(),
+                url: request.url(),
+                content_type: content_type.as_deref(),
+                body: &body,
+            })
+        };
+
+        let mut response = Response::from_string(reply.body)
+            .with_status_code(reply.status)
+            .with_header(
+                Header::from_bytes("Content-Type", "application/json").unwrap(),
+            );
+        if let Some(retry_after) = reply.retry_after {
+            let seconds = retry_after.as_secs_f64().ceil().to_string();
+            response.add_header(
+                Header::from_bytes("Retry-After", seconds).unwrap(),
+            );
+        }
+        if let Err(err) = request.respond(response) {
+            warn!("could not respond: {err}");
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Reads portable bitmaps (PBM) in the plain (`P1`) and raw (`P4`) formats.
+
+use servicepoint2::{Grid, PixelGrid, TILE_SIZE};
+
+/// Checks whether the data starts like a PBM file.
+pub fn is_pbm(data: &[u8]) -> bool {
+    data.starts_with(b"P1") || data.starts_with(b"P4")
+}
+
+/// Reads a PBM file into pixels. Black pixels in the file are on.
+///
+/// The width is padded with off pixels to the next multiple of 8.
+/// Files bigger than `max_width` by `max_height` are rejected before
+/// anything is allocated.
+pub fn read_pbm(
+    data: &[u8],
+    max_width: usize,
+    max_height: usize,
+) -> Result<PixelGrid, String> {
+    let raw = data.starts_with(b"P4");
+    let mut rest = data.get(2..).ok_or("missing PBM header")?;
+    let width = read_number(&mut rest)?;
+    let height = read_number(&mut rest)?;
+    if width > max_width || height > max_height {
+        return Err(format!(
+            "{width}x{height} is bigger than {max_width}x{max_height}"
+        ));
+    }
+
+    // exactly one whitespace character separates the header from the data
+    let data = rest.get(1..).unwrap_or_default();
+    let row_bytes = width.div_ceil(8);
+    if raw {
+        // checked before allocating, so the size is limited by the request
+        let size = row_bytes.checked_mul(height).ok_or("invalid PBM header")?;
+        if data.len() < size {
+            return Err(format!(
+                "expected {size} bytes of pixels for {width}x{height}, found {}",
+                data.len()
+            ));
+        }
+    }
+
+    let mut pixels =
+        PixelGrid::new(width.div_ceil(TILE_SIZE) * TILE_SIZE, height);
+    if raw {
+        for y in 0..height {
+            for x in 0..width {
+                let byte = data[y * row_bytes + x / 8];
+                pixels.set(x, y, byte & (0x80 >> (x % 8)) != 0);
+            }
+        }
+    } else {
+        let mut bits = rest
+            .iter()
+            .filter(|byte| !byte.is_ascii_whitespace())
+            .map(|byte| match byte {
+                b'0' => Ok(false),
+                b'1' => Ok(true),
+                other => Err(format!("unexpected pixel '{}'", *other as char)),
+            });
+        for y in 0..height {
+            for x in 0..width {
+                let bit = bits.next().ok_or_else(|| {
+                    format!("missing pixels for {width}x{height}")
+                })??;
+                pixels.set(x, y, bit);
+            }
+        }
+    }
+    Ok(pixels)
+}
+
+/// Reads a decimal number after skipping whitespace and comments.
+fn read_number(rest: &mut &[u8]) -> Result<usize, String> {
+    loop {
+        match rest.first() {
+            Some(byte) if byte.is_ascii_whitespace() => *rest = &rest[1..],
+            Some(b'#') => {
+                let end = rest
+                    .iter()
+                    .position(|byte| *byte == b'\n')
+                    .unwrap_or(rest.len());
+                *rest = &rest[end..];
+            }
+            _ => break,
+        }
+    }
+    let digits = rest.iter().take_while(|byte| byte.is_ascii_digit()).count();
+    let number = std::str::from_utf8(&rest[..digits])
+        .ok()
+        .and_then(|digits| digits.parse().ok())
+        .ok_or("invalid PBM header")?;
+    *rest = &rest[digits..];
+    Ok(number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain() {
+        let pixels = read_pbm(b"P1\n# comment\n3 2\n1 0 1\n0 1 0\n", 8, 2);
+        let pixels = pixels.unwrap();
+        assert_eq!((pixels.width(), pixels.height()), (8, 2));
+        assert!(pixels.get(0, 0) && !pixels.get(1, 0) && pixels.get(2, 0));
+        assert!(!pixels.get(0, 1) && pixels.get(1, 1) && !pixels.get(2, 1));
+
+        assert!(read_pbm(b"P1 2 2 1 0 1", 8, 8).is_err());
+        assert!(read_pbm(b"P1 1 1 2", 8, 8).is_err());
+    }
+
+    #[test]
+    fn raw() {
+        let pixels = read_pbm(b"P4\n10 1\n\xc0\x40", 16, 1).unwrap();
+        assert_eq!((pixels.width(), pixels.height()), (16, 1));
+        assert!(pixels.get(0, 0) && pixels.get(1, 0) && pixels.get(9, 0));
+        assert!(!pixels.get(2, 0) && !pixels.get(8, 0));
+
+        let error = read_pbm(b"P4\n10 2\n\xc0\x40", 16, 2).unwrap_err();
+        assert!(error.contains("expected 4 bytes"), "{error}");
+    }
+
+    #[test]
+    fn too_big() {
+        let error = read_pbm(b"P4 99999999 99999999 ", 448, 160).unwrap_err();
+        assert!(error.contains("bigger than 448x160"), "{error}");
+        let error = read_pbm(b"P1 9 1 111111111", 8, 1).unwrap_err();
+        assert!(error.contains("bigger than 8x1"), "{error}");
+        let header = format!("P4 {} 1 ", usize::MAX);
+        assert!(read_pbm(header.as_bytes(), usize::MAX, 1).is_err());
+    }
+
+    #[test]
+    fn invalid_header() {
+        assert!(read_pbm(b"P4", 8, 8).is_err());
+        assert!(read_pbm(b"P4 x 1", 8, 8).is_err());
+        assert!(read_pbm(b"P1 99999999999999999999999 1", 8, 8).is_err());
+    }
+}
@@ -0,0 +1,663 @@
+//! Extracts UDP datagrams from pcap and pcapng captures or hex dumps.
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+use log::{debug, warn};
+
+/// A single UDP datagram found in the input
+#[derive(Debug)]
+pub struct Datagram {
+    /// time of capture, relative to an unspecified epoch
+    pub timestamp: Duration,
+    /// sender, unknown for hex dumps
+    pub source: Option<SocketAddr>,
+    /// receiver, unknown for hex dumps
+    pub destination: Option<SocketAddr>,
+    pub payload: Vec<u8>,
+}
+
+const PCAP_MAGIC_MICROS: u32 = 0xa1b2_c3d4;
+const PCAP_MAGIC_NANOS: u32 = 0xa1b2_3c4d;
+const PCAPNG_SECTION_HEADER: u32 = 0x0a0d_0d0a;
+const PCAPNG_BYTE_ORDER_MAGIC: u32 = 0x1a2b_3c4d;
+
+const LINKTYPE_NULL: u32 = 0;
+const LINKTYPE_ETHERNET: u32 = 1;
+const LINKTYPE_RAW: u32 = 101;
+const LINKTYPE_LINUX_SLL: u32 = 113;
+const LINKTYPE_IPV4: u32 = 228;
+const LINKTYPE_IPV6: u32 = 229;
+const LINKTYPE_LINUX_SLL2: u32 = 276;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_IPV6: u16 = 0x86dd;
+const ETHERTYPE_VLAN: u16 = 0x8100;
+
+const IP_PROTOCOL_UDP: u8 = 17;
+
+/// Reads all UDP datagrams from or to the port.
+///
+/// The format is detected by the magic number at the start of the data. Anything that is not a
+/// pcap or pcapng file gets parsed as a hex dump, see `read_hex`.
+pub fn read(data: &[u8], port: u16) -> Result<Vec<Datagram>, String> {
+    let mut extractor = UdpExtractor::new(port);
+    match data.get(0..4).map(|magic| read_u32(magic, false)) {
+        Some(PCAP_MAGIC_MICROS | PCAP_MAGIC_NANOS) => {
+            read_pcap(data, false, &mut extractor)?
+        }
+        Some(magic)
+            if magic.swap_bytes() == PCAP_MAGIC_MICROS
+                || magic.swap_bytes() == PCAP_MAGIC_NANOS =>
+        {
+            read_pcap(data, true, &mut extractor)?
+        }
+        Some(PCAPNG_SECTION_HEADER) => read_pcapng(data, &mut extractor)?,
+        _ => {
+            let text = std::str::from_utf8(data)
+                .map_err(|_| "input is neither a capture nor a hex dump")?;
+            return read_hex(text);
+        }
+    }
+    Ok(extractor.datagrams)
+}
+
+/// Parses a hex dump with one datagram per line.
+///
+/// Whitespace and `:` between the hex digits are ignored, as are empty lines and lines starting
+/// with `#`. A line may start with `@<seconds>` followed by a space to specify a timestamp,
+/// otherwise the datagram is placed one `FRAME_PACING` after the previous one.
+pub fn read_hex(text: &str) -> Result<Vec<Datagram>, String> {
+    let mut datagrams = vec![];
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (timestamp, hex) = match line.strip_prefix('@') {
+            None => {
+                let previous = datagrams.last().map(|d: &Datagram| d.timestamp);
+                let timestamp = previous.map_or(Duration::ZERO, |t| {
+                    t + servicepoint2::FRAME_PACING
+                });
+                (timestamp, line)
+            }
+            Some(rest) => {
+                let (seconds, hex) = rest.split_once(' ').unwrap_or((rest, ""));
+                let seconds: f64 = seconds.parse().map_err(|_| {
+                    format!("line {}: invalid timestamp {seconds}", index + 1)
+                })?;
+                let timestamp =
+                    Duration::try_from_secs_f64(seconds).map_err(|err| {
+                        format!(
+                            "line {}: invalid timestamp {seconds}: {err}",
+                            index + 1
+                        )
+                    })?;
+                (timestamp, hex)
+            }
+        };
+
+        let digits: Vec<u8> = hex
+            .bytes()
+            .filter(|c| !c.is_ascii_whitespace() && *c != b':')
+            .collect();
+        if digits.len() % 2 == 1 {
+            return Err(format!(
+                "line {}: odd number of hex digits",
+                index + 1
+            ));
+        }
+
+        let payload = digits
+            .chunks(2)
+            .map(|pair| {
+                let pair = std::str::from_utf8(pair).unwrap_or("");
+                u8::from_str_radix(pair, 16)
+            })
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| format!("line {}: invalid hex digit", index + 1))?;
+
+        datagrams.push(Datagram {
+            timestamp,
+            source: None,
+            destination: None,
+            payload,
+        });
+    }
+    Ok(datagrams)
+}
+
+fn read_pcap(
+    data: &[u8],
+    swapped: bool,
+    extractor: &mut UdpExtractor,
+) -> Result<(), String> {
+    if data.len() < 24 {
+        return Err("truncated pcap header".into());
+    }
+    let nanos = read_u32(&data[0..4], swapped) == PCAP_MAGIC_NANOS;
+    let link_type = read_u32(&data[20..24], swapped) & 0x0fff_ffff;
+
+    let mut rest = &data[24..];
+    while rest.len() >= 16 {
+        let seconds = read_u32(&rest[0..4], swapped);
+        let fraction = read_u32(&rest[4..8], swapped);
+        let captured = read_u32(&rest[8..12], swapped) as usize;
+        let Some(frame) = rest.get(16..16 + captured) else {
+            warn!("truncated pcap record");
+            break;
+        };
+
+        let fraction = if nanos {
+            Duration::from_nanos(fraction.into())
+        } else {
+            Duration::from_micros(fraction.into())
+        };
+        let timestamp = Duration::from_secs(seconds.into()) + fraction;
+        extractor.link_frame(link_type, timestamp, frame);
+        rest = &rest[16 + captured..];
+    }
+    Ok(())
+}
+
+/// The state of an interface described in a pcapng file
+struct Interface {
+    link_type: u32,
+    /// length of a timestamp tick
+    resolution: Duration,
+}
+
+fn read_pcapng(
+    data: &[u8],
+    extractor: &mut UdpExtractor,
+) -> Result<(), String> {
+    let mut swapped = false;
+    let mut interfaces: Vec<Interface> = vec![];
+    let mut rest = data;
+    let mut last_timestamp = Duration::ZERO;
+
+    while rest.len() >= 12 {
+        let block_type = read_u32(&rest[0..4], false);
+        if block_type == PCAPNG_SECTION_HEADER {
+            let byte_order = read_u32(&rest[8..12], false);
+            swapped = byte_order != PCAPNG_BYTE_ORDER_MAGIC;
+            if swapped && byte_order.swap_bytes() != PCAPNG_BYTE_ORDER_MAGIC {
+                return Err("invalid pcapng byte order magic".into());
+            }
+            interfaces.clear();
+        }
+
+        let block_type = read_u32(&rest[0..4], swapped);
+        let length = read_u32(&rest[4..8], swapped) as usize;
+        if length < 12 || length > rest.len() {
+            return Err("truncated pcapng block".into());
+        }
+        let body = &rest[8..length - 4];
+        rest = &rest[length..];
+
+        match block_type {
+            // interface description
+            1 if body.len() >= 8 => {
+                let link_type = u32::from(read_u16(&body[0..2], swapped));
+                let resolution = read_resolution(&body[8..], swapped);
+                interfaces.push(Interface {
+                    link_type,
+                    resolution,
+                });
+            }
+            // enhanced packet
+            6 if body.len() >= 20 => {
+                let interface = read_u32(&body[0..4], swapped) as usize;
+                let Some(interface) = interfaces.get(interface) else {
+                    warn!("packet for unknown interface {interface}");
+                    continue;
+                };
+                let ticks = (u64::from(read_u32(&body[4..8], swapped)) << 32)
+                    | u64::from(read_u32(&body[8..12], swapped));
+                let captured = read_u32(&body[12..16], swapped) as usize;
+                let Some(frame) = body.get(20..20 + captured) else {
+                    warn!("truncated enhanced packet block");
+                    continue;
+                };
+
+                last_timestamp = ticks_to_duration(ticks, interface.resolution);
+                extractor.link_frame(
+                    interface.link_type,
+                    last_timestamp,
+                    frame,
+                );
+            }
+            // simple packet, which has no timestamp
+            3 if body.len() >= 4 => {
+                let Some(interface) = interfaces.first() else {
+                    warn!("simple packet without interface");
+                    continue;
+                };
+                extractor.link_frame(
+                    interface.link_type,
+                    last_timestamp,
+                    &body[4..],
+                );
+            }
+            _ => debug!("skipping pcapng block of type {block_type:#x}"),
+        }
+    }
+    Ok(())
+}
+
+/// Reads the `if_tsresol` option of an interface description block
+fn read_resolution(mut options: &[u8], swapped: bool) -> Duration {
+    let mut resolution = Duration::from_micros(1);
+    while options.len() >= 4 {
+        let code = read_u16(&options[0..2], swapped);
+        let length = read_u16(&options[2..4], swapped) as usize;
+        let padded = (length + 3) & !3;
+        let Some(value) = options.get(4..4 + length) else {
+            break;
+        };
+
+        match code {
+            0 => break,
+            9 if length == 1 => {
+                let exponent = u32::from(value[0] & 0x7f);
+                let ticks_per_second = if value[0] & 0x80 == 0 {
+                    10u64.checked_pow(exponent)
+                } else {
+                    1u64.checked_shl(exponent)
+                };
+                if let Some(ticks) = ticks_per_second.filter(|t| *t != 0) {
+                    resolution = Duration::from_secs_f64(1.0 / ticks as f64);
+                }
+            }
+            _ => {}
+        }
+        options = options.get(4 + padded..).unwrap_or_default();
+    }
+    resolution
+}
+
+fn ticks_to_duration(ticks: u64, resolution: Duration) -> Duration {
+    if resolution.as_nanos() > 0 {
+        Duration::from_nanos(
+            (ticks as u128 * resolution.as_nanos()).min(u64::MAX as u128)
+                as u64,
+        )
+    } else {
+        Duration::from_secs_f64(ticks as f64 * resolution.as_secs_f64())
+    }
+}
+
+/// Key of an IPv4 datagram that is being reassembled
+type FragmentKey = (Ipv4Addr, Ipv4Addr, u16);
+
+/// The fragments of an IPv4 datagram received so far
+#[derive(Default)]
+struct Fragments {
+    /// fragment payloads by byte offset
+    parts: BTreeMap<usize, Vec<u8>>,
+    /// known once the last fragment arrived
+    total_length: Option<usize>,
+}
+
+/// Walks the protocol layers of captured frames and collects the UDP datagrams.
+struct UdpExtractor {
+    port: u16,
+    fragments: HashMap<FragmentKey, Fragments>,
+    datagrams: Vec<Datagram>,
+}
+
+impl UdpExtractor {
+    fn new(port: u16) -> Self {
+        Self {
+            port,
+            fragments: HashMap::new(),
+            datagrams: vec![],
+        }
+    }
+
+    fn link_frame(
+        &mut self,
+        link_type: u32,
+        timestamp: Duration,
+        frame: &[u8],
+    ) {
+        let ip = match link_type {
+            LINKTYPE_ETHERNET => ethernet_payload(frame),
+            LINKTYPE_NULL => frame.get(4..),
+            LINKTYPE_RAW | LINKTYPE_IPV4 | LINKTYPE_IPV6 => Some(frame),
+            LINKTYPE_LINUX_SLL => frame.get(16..),
+            LINKTYPE_LINUX_SLL2 => frame.get(20..),
+            _ => {
+                warn!("unsupported link type {link_type}");
+                None
+            }
+        };
+
+        match ip.and_then(|ip| ip.first()).map(|byte| byte >> 4) {
+            Some(4) => self.ipv4(timestamp, ip.unwrap_or_default()),
+            Some(6) => self.ipv6(timestamp, ip.unwrap_or_default()),
+            _ => debug!("skipping non-IP frame"),
+        }
+    }
+
+    fn ipv4(&mut self, timestamp: Duration, packet: &[u8]) {
+        if packet.len() < 20 {
+            return;
+        }
+        let header_length = (packet[0] & 0x0f) as usize * 4;
+        let total_length =
+            (read_u16(&packet[2..4], true) as usize).min(packet.len());
+        if packet[9] != IP_PROTOCOL_UDP || header_length > total_length {
+            return;
+        }
+
+        let id = read_u16(&packet[4..6], true);
+        let flags_offset = read_u16(&packet[6..8], true);
+        let more_fragments = flags_offset & 0x2000 != 0;
+        let fragment_offset = (flags_offset & 0x1fff) as usize * 8;
+        let source =
+            Ipv4Addr::new(packet[12], packet[13], packet[14], packet[15]);
+        let destination =
+            Ipv4Addr::new(packet[16], packet[17], packet[18], packet[19]);
+        let payload = &packet[header_length..total_length];
+
+        if !more_fragments && fragment_offset == 0 {
+            self.udp(timestamp, source.into(), destination.into(), payload);
+            return;
+        }
+
+        let key = (source, destination, id);
+        let fragments = self.fragments.entry(key).or_default();
+        fragments.parts.insert(fragment_offset, payload.to_vec());
+        if !more_fragments {
+            fragments.total_length = Some(fragment_offset + payload.len());
+        }
+
+        let Some(total_length) = fragments.total_length else {
+            return;
+        };
+        let mut reassembled = Vec::with_capacity(total_length);
+        for (offset, part) in &fragments.parts {
+            if *offset != reassembled.len() {
+                return; // still missing a fragment
+            }
+            reassembled.extend_from_slice(part);
+        }
+
+        self.fragments.remove(&key);
+        self.udp(timestamp, source.into(), destination.into(), &reassembled);
+    }
+
+    fn ipv6(&mut self, timestamp: Duration, packet: &[u8]) {
+        if packet.len() < 40 {
+            return;
+        }
+        if packet[6] != IP_PROTOCOL_UDP {
+            debug!("skipping IPv6 packet with next header {}", packet[6]);
+            return;
+        }
+        let payload_length = read_u16(&packet[4..6], true) as usize;
+        let address = |bytes: &[u8]| {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(bytes);
+            IpAddr::from(Ipv6Addr::from(octets))
+        };
+        let end = (40 + payload_length).min(packet.len());
+        self.udp(
+            timestamp,
+            address(&packet[8..24]),
+            address(&packet[24..40]),
+            &packet[40..end],
+        );
+    }
+
+    fn udp(
+        &mut self,
+        timestamp: Duration,
+        source: IpAddr,
+        destination: IpAddr,
+        segment: &[u8],
+    ) {
+        if segment.len() < 8 {
+            return;
+        }
+        let source_port = read_u16(&segment[0..2], true);
+        let destination_port = read_u16(&segment[2..4], true);
+        if source_port != self.port && destination_port != self.port {
+            return;
+        }
+
+        let length =
+            (read_u16(&segment[4..6], true) as usize).clamp(8, segment.len());
+        self.datagrams.push(Datagram {
+            timestamp,
+            source: Some(SocketAddr::new(source, source_port)),
+            destination: Some(SocketAddr::new(destination, destination_port)),
+            payload: segment[8..length].to_vec(),
+        });
+    }
+}
+
+fn ethernet_payload(frame: &[u8]) -> Option<&[u8]> {
+    let mut ether_type = read_u16(frame.get(12..14)?, true);
+    let mut rest = frame.get(14..)?;
+    if ether_type == ETHERTYPE_VLAN {
+        ether_type = read_u16(rest.get(2..4)?, true);
+        rest = rest.get(4..)?;
+    }
+    match ether_type {
+        ETHERTYPE_IPV4 | ETHERTYPE_IPV6 => Some(rest),
+        _ => None,
+    }
+}
+
+/// Reads a little endian u16, or a big endian one if `swapped` is set
+fn read_u16(bytes: &[u8], swapped: bool) -> u16 {
+    let value = u16::from_le_bytes([bytes[0], bytes[1]]);
+    if swapped {
+        value.swap_bytes()
+    } else {
+        value
+    }
+}
+
+/// Reads a little endian u32, or a big endian one if `swapped` is set
+fn read_u32(bytes: &[u8], swapped: bool) -> u32 {
+    let value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    if swapped {
+        value.swap_bytes()
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use std::time::Duration;
+
+    use super::{read, LINKTYPE_ETHERNET, LINKTYPE_RAW};
+
+    const SOURCE: [u8; 4] = [10, 0, 0, 1];
+    const DESTINATION: [u8; 4] = [10, 0, 0, 2];
+
+    /// An IPv4 packet carrying `payload`, which starts with the UDP header for the first
+    /// fragment
+    fn ipv4(id: u16, flags_offset: u16, payload: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0x45, 0];
+        packet.extend_from_slice(&(20 + payload.len() as u16).to_be_bytes());
+        packet.extend_from_slice(&id.to_be_bytes());
+        packet.extend_from_slice(&flags_offset.to_be_bytes());
+        packet.extend_from_slice(&[64, 17, 0, 0]);
+        packet.extend_from_slice(&SOURCE);
+        packet.extend_from_slice(&DESTINATION);
+        packet.extend_from_slice(payload);
+        packet
+    }
+
+    fn udp(port: u16, payload: &[u8]) -> Vec<u8> {
+        let mut segment = vec![];
+        segment.extend_from_slice(&50000u16.to_be_bytes());
+        segment.extend_from_slice(&port.to_be_bytes());
+        segment.extend_from_slice(&(8 + payload.len() as u16).to_be_bytes());
+        segment.extend_from_slice(&[0, 0]);
+        segment.extend_from_slice(payload);
+        segment
+    }
+
+    /// A little endian pcap file with microsecond timestamps
+    fn pcap(link_type: u32, frames: &[(u32, &[u8])]) -> Vec<u8> {
+        let mut file = vec![];
+        file.extend_from_slice(&0xa1b2_c3d4u32.to_le_bytes());
+        file.extend_from_slice(&[2, 0, 4, 0]);
+        file.extend_from_slice(&[0; 8]);
+        file.extend_from_slice(&65535u32.to_le_bytes());
+        file.extend_from_slice(&link_type.to_le_bytes());
+        for (micros, frame) in frames {
+            file.extend_from_slice(&1u32.to_le_bytes());
+            file.extend_from_slice(&micros.to_le_bytes());
+            file.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            file.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            file.extend_from_slice(frame);
+        }
+        file
+    }
+
+    fn pcapng_block(block_type: u32, body: &[u8]) -> Vec<u8> {
+        let mut body = body.to_vec();
+        body.resize(body.len().div_ceil(4) * 4, 0);
+        let length = (body.len() + 12) as u32;
+        let mut block = vec![];
+        block.extend_from_slice(&block_type.to_le_bytes());
+        block.extend_from_slice(&length.to_le_bytes());
+        block.extend_from_slice(&body);
+        block.extend_from_slice(&length.to_le_bytes());
+        block
+    }
+
+    #[test]
+    fn pcap_raw_ip() {
+        let frame = ipv4(1, 0, &udp(2342, b"hello"));
+        let file = pcap(LINKTYPE_RAW, &[(500, &frame)]);
+
+        let datagrams = read(&file, 2342).unwrap();
+        assert_eq!(datagrams.len(), 1);
+        let datagram = &datagrams[0];
+        assert_eq!(datagram.payload, b"hello");
+        assert_eq!(datagram.timestamp, Duration::from_micros(1_000_500));
+        assert_eq!(datagram.source, Some(SocketAddr::from((SOURCE, 50000))));
+        assert_eq!(
+            datagram.destination,
+            Some(SocketAddr::from((DESTINATION, 2342)))
+        );
+    }
+
+    #[test]
+    fn pcap_ethernet_filters_port() {
+        let mut frame = vec![0; 12];
+        frame.extend_from_slice(&0x0800u16.to_be_bytes());
+        let mut other = frame.clone();
+        frame.extend(ipv4(1, 0, &udp(2342, b"display")));
+        other.extend(ipv4(2, 0, &udp(53, b"dns")));
+        let file = pcap(LINKTYPE_ETHERNET, &[(0, &other), (1, &frame)]);
+
+        let datagrams = read(&file, 2342).unwrap();
+        assert_eq!(datagrams.len(), 1);
+        assert_eq!(datagrams[0].payload, b"display");
+    }
+
+    #[test]
+    fn reassembles_fragments() {
+        let segment = udp(2342, &[7u8; 40]);
+        // offsets are in units of 8 bytes, 0x2000 is "more fragments"
+        let first = ipv4(9, 0x2000, &segment[..24]);
+        let second = ipv4(9, 0x2000 | 3, &segment[24..40]);
+        let last = ipv4(9, 5, &segment[40..]);
+        // out of order on purpose
+        let file = pcap(LINKTYPE_RAW, &[(0, &second), (1, &last), (2, &first)]);
+
+        let datagrams = read(&file, 2342).unwrap();
+        assert_eq!(datagrams.len(), 1);
+        assert_eq!(datagrams[0].payload, vec![7u8; 40]);
+    }
+
+    #[test]
+    fn missing_fragment_is_skipped() {
+        let segment = udp(2342, &[7u8; 40]);
+        let first = ipv4(9, 0x2000, &segment[..24]);
+        let last = ipv4(9, 5, &segment[40..]);
+        let file = pcap(LINKTYPE_RAW, &[(0, &first), (1, &last)]);
+
+        assert!(read(&file, 2342).unwrap().is_empty());
+    }
+
+    #[test]
+    fn truncated_pcap() {
+        let frame = ipv4(1, 0, &udp(2342, b"hello"));
+        let file = pcap(LINKTYPE_RAW, &[(0, &frame), (1, &frame)]);
+
+        // the second record is cut off, the first one is still read
+        let datagrams = read(&file[..file.len() - 3], 2342).unwrap();
+        assert_eq!(datagrams.len(), 1);
+
+        assert!(read(&file[..20], 2342).is_err());
+    }
+
+    #[test]
+    fn pcapng() {
+        let mut section = vec![];
+        section.extend_from_slice(&0x1a2b_3c4du32.to_le_bytes());
+        section.extend_from_slice(&[1, 0, 0, 0]);
+        section.extend_from_slice(&[0xff; 8]);
+        let mut interface = vec![];
+        interface.extend_from_slice(&(LINKTYPE_RAW as u16).to_le_bytes());
+        interface.extend_from_slice(&[0; 6]);
+        // if_tsresol of 10^-3, then the end of options
+        interface.extend_from_slice(&[9, 0, 1, 0, 3, 0, 0, 0, 0, 0, 0, 0]);
+        let frame = ipv4(1, 0, &udp(2342, b"ng"));
+        let mut packet = vec![0; 4];
+        packet.extend_from_slice(&0u32.to_le_bytes());
+        packet.extend_from_slice(&1500u32.to_le_bytes());
+        packet.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+        packet.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+        packet.extend_from_slice(&frame);
+
+        let mut file = pcapng_block(0x0a0d_0d0a, &section);
+        file.extend(pcapng_block(1, &interface));
+        file.extend(pcapng_block(6, &packet));
+
+        let datagrams = read(&file, 2342).unwrap();
+        assert_eq!(datagrams.len(), 1);
+        assert_eq!(datagrams[0].payload, b"ng");
+        assert_eq!(datagrams[0].timestamp, Duration::from_millis(1500));
+
+        assert!(read(&file[..file.len() - 4], 2342).is_err());
+    }
+
+    #[test]
+    fn hex_dump() {
+        let text = "# comment\n00 02 00 00\n\n@2.5 0003:0000\n0004";
+        let datagrams = read(text.as_bytes(), 2342).unwrap();
+        assert_eq!(datagrams.len(), 3);
+        assert_eq!(datagrams[0].payload, [0, 2, 0, 0]);
+        assert_eq!(datagrams[0].timestamp, Duration::ZERO);
+        assert_eq!(datagrams[1].timestamp, Duration::from_millis(2500));
+        assert_eq!(
+            datagrams[2].timestamp,
+            Duration::from_millis(2500) + servicepoint2::FRAME_PACING
+        );
+        assert_eq!(datagrams[0].source, None);
+    }
+
+    #[test]
+    fn invalid_hex_dump() {
+        assert!(read(b"@-1 0002", 2342).is_err());
+        assert!(read(b"@NaN 0002", 2342).is_err());
+        assert!(read(b"@abc 0002", 2342).is_err());
+        assert!(read(b"000", 2342).is_err());
+        assert!(read(b"00zz", 2342).is_err());
+    }
+}
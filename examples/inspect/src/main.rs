@@ -0,0 +1,142 @@
+use std::io::Read;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::Parser;
+
+use servicepoint2::{
+    render_pixels, Command, Grid, Packet, PacketRef, ScreenState, TerminalStyle,
+};
+
+mod capture;
+
+#[derive(Parser, Debug)]
+struct Cli {
+    /// pcap or pcapng file, or a hex dump with one datagram per line. Reads stdin if omitted.
+    input: Option<PathBuf>,
+    /// only datagrams from or to this UDP port are decoded
+    #[arg(short, long, default_value_t = 2342)]
+    port: u16,
+    /// print the reconstructed screen as it was this many seconds after the first datagram
+    #[arg(short, long, value_parser = parse_seconds)]
+    render_at: Vec<Duration>,
+    /// print the contents of grids contained in commands
+    #[arg(short, long)]
+    verbose: bool,
+    /// draw the reconstructed screen with Braille characters, which is smaller
+    #[arg(short, long)]
+    braille: bool,
+}
+
+/// example: `tcpdump -i any -w capture.pcap udp port 2342`, then
+/// `cargo run --bin servicepoint-inspect -- capture.pcap --render-at 1.5`
+fn main() {
+    env_logger::init();
+    let cli = Cli::parse();
+
+    let data = match &cli.input {
+        Some(path) => std::fs::read(path).expect("could not read input file"),
+        None => {
+            let mut data = vec![];
+            std::io::stdin()
+                .read_to_end(&mut data)
+                .expect("could not read stdin");
+            data
+        }
+    };
+
+    let datagrams = match capture::read(&data, cli.port) {
+        Ok(datagrams) => datagrams,
+        Err(err) => {
+            eprintln!("could not read input: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    let mut render_at = cli.render_at;
+    render_at.sort();
+    render_at.reverse();
+
+    let start = datagrams
+        .iter()
+        .map(|datagram| datagram.timestamp)
+        .min()
+        .unwrap_or_default();
+    let mut state = ScreenState::new();
+    let mut errors = 0;
+
+    for (index, datagram) in datagrams.iter().enumerate() {
+        let time = datagram.timestamp.saturating_sub(start);
+        while render_at.last().is_some_and(|at| *at < time) {
+            render(&state, render_at.pop().unwrap(), cli.braille);
+        }
+
+        let route = match (datagram.source, datagram.destination) {
+            (Some(source), Some(destination)) => {
+                format!(" {source} -> {destination}")
+            }
+            _ => String::new(),
+        };
+        print!("[{:>12.6}] #{}{route}: ", time.as_secs_f64(), index + 1);
+
+        let packet = match PacketRef::try_from(&*datagram.payload) {
+            Ok(packet) => packet,
+            Err(()) => {
+                errors += 1;
+                println!(
+                    "error: {} bytes are too short for a packet",
+                    datagram.payload.len()
+                );
+                continue;
+            }
+        };
+
+        match Command::try_from(Packet::from(packet)) {
+            Ok(command) if cli.verbose => {
+                println!("{command:#}");
+                state.apply(&command);
+            }
+            Ok(command) => {
+                println!("{command}");
+                state.apply(&command);
+            }
+            Err(err) => {
+                errors += 1;
+                println!("error: {err:?} in {packet}");
+            }
+        }
+    }
+
+    while let Some(at) = render_at.pop() {
+        render(&state, at, cli.braille);
+    }
+
+    println!(
+        "{} datagrams on port {}, {errors} could not be decoded",
+        datagrams.len(),
+        cli.port
+    );
+}
+
+fn parse_seconds(arg: &str) -> Result<Duration, String> {
+    let seconds: f64 = arg.parse().map_err(|_| "expected seconds")?;
+    Duration::try_from_secs_f64(seconds).map_err(|err| err.to_string())
+}
+
+fn render(state: &ScreenState, at: Duration, braille: bool) {
+    println!("--- screen at {:.6}s ---", at.as_secs_f64());
+    if braille {
+        println!("{}", render_pixels(state.pixels(), TerminalStyle::Braille));
+    } else {
+        println!("{}", state.pixels());
+    }
+
+    let chars = state.chars();
+    let has_text = (0..chars.height())
+        .any(|y| (0..chars.width()).any(|x| chars.get(x, y) != 0));
+    if has_text {
+        println!("--- text at {:.6}s ---", at.as_secs_f64());
+        println!("{chars}");
+    }
+    println!("---");
+}
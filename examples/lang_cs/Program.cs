@@ -4,7 +4,7 @@ using CompressionCode = ServicePoint2.BindGen.CompressionCode;
 using var connection = Connection.Open("127.0.0.1:2342");
 
 connection.Send(Command.Clear().IntoPacket());
-connection.Send(Command.Brightness(128).IntoPacket());
+connection.Send(Command.Brightness(Constants.MaxBrightness).IntoPacket());
 
 using var pixels = PixelGrid.New(Constants.PixelWidth, Constants.PixelHeight);
 
@@ -0,0 +1,30 @@
+import time
+
+from servicepoint2 import (
+    Command,
+    CompressionCode,
+    Connection,
+    PixelGrid,
+    FRAME_PACING,
+    MAX_BRIGHTNESS,
+    PIXEL_HEIGHT,
+    PIXEL_WIDTH,
+)
+
+connection = Connection.open("127.0.0.1:2342")
+
+connection.send(Command.clear())
+connection.send(Command.brightness(MAX_BRIGHTNESS))
+
+pixels = PixelGrid(PIXEL_WIDTH, PIXEL_HEIGHT)
+
+offset = 0
+while True:
+    pixels.fill(False)
+
+    for y in range(pixels.height):
+        pixels[(y + offset) % PIXEL_WIDTH, y] = True
+
+    connection.send(Command.bitmap_linear_win(0, 0, pixels, CompressionCode.Lzma))
+    offset += 1
+    time.sleep(FRAME_PACING)
@@ -1,5 +1,3 @@
-use std::thread;
-
 use clap::Parser;
 
 use servicepoint2::*;
@@ -15,23 +13,20 @@ fn main() {
 
     let connection = Connection::open(Cli::parse().destination).unwrap();
 
-    let mut pixels = PixelGrid::max_sized();
-    for x_offset in 0..usize::MAX {
-        pixels.fill(false);
+    let mut animator = Animator::new();
+    animator.set_compression(CompressionCode::Lzma);
+    let handle = animator.handle();
+    ctrlc::set_handler(move || handle.stop()).unwrap();
 
-        for y in 0..PIXEL_HEIGHT {
-            pixels.set((y + x_offset) % PIXEL_WIDTH, y, true);
-        }
-        connection
-            .send(
-                Command::BitmapLinearWin(
-                    Origin(0, 0),
-                    pixels.clone(),
-                    CompressionCode::Lzma,
-                )
-                .into(),
-            )
-            .unwrap();
-        thread::sleep(FRAME_PACING);
-    }
+    let mut x_offset = 0;
+    let stats = animator
+        .run(&connection, &mut PixelGrid::max_sized(), |pixels, _| {
+            pixels.fill(false);
+            for y in 0..PIXEL_HEIGHT {
+                pixels.set((y + x_offset) % PIXEL_WIDTH, y, true);
+            }
+            x_offset += 1;
+        })
+        .unwrap();
+    println!("sent {} frames, dropped {}", stats.sent, stats.dropped);
 }
@@ -0,0 +1,195 @@
+use std::thread;
+use std::time::Duration;
+
+use clap::Parser;
+use log::{info, warn};
+use rumqttc::{Client, Event, Incoming, LastWill, MqttOptions, QoS};
+use serde_json::json;
+
+use servicepoint2::Connection;
+
+use crate::translate::{translate, TOPICS};
+
+mod translate;
+
+/// How long to wait before reconnecting to the broker
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Parser, Debug)]
+struct Cli {
+    /// host name of the MQTT broker
+    #[arg(short, long, default_value = "localhost")]
+    broker: String,
+    #[arg(short, long, default_value_t = 1883)]
+    port: u16,
+    #[arg(short, long, default_value = "localhost:2342")]
+    destination: String,
+    /// topics are below this, e.g. `servicepoint/text`
+    #[arg(long, default_value = "servicepoint")]
+    prefix: String,
+    #[arg(long, default_value = "servicepoint-mqtt")]
+    client_id: String,
+    #[arg(long, requires = "password")]
+    username: Option<String>,
+    #[arg(long, requires = "username")]
+    password: Option<String>,
+}
+
+/// example: `cargo run --bin servicepoint-mqtt -- --broker localhost`, then
+/// `mosquitto_pub -t servicepoint/text -m "Hello, CCCB"`
+fn main() {
+    env_logger::init();
+    let cli = Cli::parse();
+
+    let mut options = MqttOptions::new(&cli.client_id, &cli.broker, cli.port);
+    if let (Some(username), Some(password)) = (cli.username, cli.password) {
+        options.set_credentials(username, password);
+    }
+    let display =
+        Connection::open(&cli.destination).expect("could not open connection");
+    run(options, &cli.prefix, &display);
+}
+
+/// Forwards messages to the display until the process is stopped.
+///
+/// The result of every message is published to `{prefix}/status`. `{prefix}/online` is
+/// retained and changes to `false` when the connection to the broker is lost.
+fn run(mut options: MqttOptions, prefix: &str, display: &Connection) {
+    let online_topic = format!("{prefix}/online");
+    options.set_keep_alive(Duration::from_secs(10));
+    options.set_last_will(LastWill::new(
+        &online_topic,
+        "false",
+        QoS::AtLeastOnce,
+        true,
+    ));
+    let (client, mut connection) = Client::new(options, 16);
+
+    for event in connection.iter() {
+        let message = match event {
+            Ok(Event::Incoming(Incoming::ConnAck(_))) => {
+                // subscriptions are lost when reconnecting without a session
+                info!("connected to broker");
+                for topic in TOPICS {
+                    let topic = format!("{prefix}/{topic}");
+                    if let Err(err) =
+                        client.try_subscribe(topic, QoS::AtLeastOnce)
+                    {
+                        warn!("could not subscribe: {err}");
+                    }
+                }
+                publish(&client, &online_topic, "true", true);
+                continue;
+            }
+            Ok(Event::Incoming(Incoming::Publish(message))) => message,
+            Ok(_) => continue,
+            Err(err) => {
+                warn!("connection to broker failed: {err}");
+                thread::sleep(RECONNECT_DELAY);
+                continue;
+            }
+        };
+
+        let topic = message
+            .topic
+            .strip_prefix(prefix)
+            .and_then(|topic| topic.strip_prefix('/'))
+            .unwrap_or(&message.topic);
+        let reply = match translate(topic, &message.payload) {
+            Ok(packet) => {
+                let summary = packet.to_string();
+                match display.send(packet) {
+                    Ok(()) => {
+                        info!("{topic}: sent {summary}");
+                        status(topic, "sent", &summary)
+                    }
+                    Err(err) => {
+                        warn!("could not send to display: {err}");
+                        status(topic, "error", &err.to_string())
+                    }
+                }
+            }
+            Err(message) => {
+                warn!("{topic}: {message}");
+                status(topic, "error", &message)
+            }
+        };
+        publish(&client, &format!("{prefix}/status"), &reply, false);
+    }
+}
+
+/// A JSON object like `{"topic":"text","sent":"..."}`
+fn status(topic: &str, key: &str, value: &str) -> String {
+    json!({ "topic": topic, key: value }).to_string()
+}
+
+fn publish(client: &Client, topic: &str, payload: &str, retain: bool) {
+    // not waiting for space, as the queue is emptied by this thread
+    if let Err(err) =
+        client.try_publish(topic, QoS::AtLeastOnce, retain, payload)
+    {
+        warn!("could not publish to {topic}: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::UdpSocket;
+    use std::thread;
+    use std::time::Duration;
+
+    use rumqttc::{Client, Event, Incoming, MqttOptions, QoS};
+
+    use servicepoint2::{Command, Connection, Packet};
+
+    #[test]
+    fn status_is_json() {
+        let status =
+            super::status("te\"xt", "error", "'\\' is not \"CP437\"\n");
+        let status: serde_json::Value = serde_json::from_str(&status).unwrap();
+        assert_eq!(status["topic"], "te\"xt");
+        assert_eq!(status["error"], "'\\' is not \"CP437\"\n");
+    }
+
+    #[test]
+    #[ignore = "needs an MQTT broker like mosquitto on localhost:1883"]
+    fn forwards_through_broker() {
+        let display = UdpSocket::bind("127.0.0.1:0").unwrap();
+        display
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let connection =
+            Connection::open(display.local_addr().unwrap()).unwrap();
+        thread::spawn(move || {
+            let options = MqttOptions::new("bridge-test", "localhost", 1883);
+            super::run(options, "servicepoint-test", &connection);
+        });
+
+        let options = MqttOptions::new("sender-test", "localhost", 1883);
+        let (client, mut events) = Client::new(options, 16);
+        client
+            .subscribe("servicepoint-test/status", QoS::AtLeastOnce)
+            .unwrap();
+        // give the bridge time to subscribe
+        thread::sleep(Duration::from_secs(1));
+        client
+            .publish("servicepoint-test/clear", QoS::AtLeastOnce, false, "")
+            .unwrap();
+
+        let status = events
+            .iter()
+            .find_map(|event| match event {
+                Ok(Event::Incoming(Incoming::Publish(publish))) => {
+                    Some(publish.payload)
+                }
+                _ => None,
+            })
+            .unwrap();
+        assert!(String::from_utf8_lossy(&status).contains("\"sent\""));
+
+        let mut buffer = [0u8; 100];
+        let size = display.recv(&mut buffer).unwrap();
+        let packet = Packet::try_from(&buffer[..size]).unwrap();
+        assert_eq!(Command::try_from(packet).unwrap(), Command::Clear);
+    }
+}
@@ -0,0 +1,124 @@
+//! Turns MQTT messages into packets for the display.
+
+use servicepoint2::{
+    text_to_cp437, Brightness, BrightnessOutOfRange, Command, Grid, Origin,
+    Packet, TILE_HEIGHT, TILE_WIDTH,
+};
+
+/// The topics below the prefix that are subscribed to
+pub const TOPICS: [&str; 4] = ["text", "brightness", "clear", "raw"];
+
+/// Translates the payload of a message to `{prefix}/{topic}`.
+///
+/// - `text`: UTF-8 text shown at the top left, lines are separated by `\n`.
+///   At most 56 characters and 20 lines fit on the screen.
+/// - `brightness`: a number from 0 to 11
+/// - `clear`: the payload is ignored
+/// - `raw`: a packet as bytes or as hex digits
+pub fn translate(topic: &str, payload: &[u8]) -> Result<Packet, String> {
+    let command = match topic {
+        "text" => {
+            let text = std::str::from_utf8(payload)
+                .map_err(|_| "the text is not valid UTF-8")?;
+            let grid = text_to_cp437(text).map_err(|err| err.to_string())?;
+            if grid.width() > TILE_WIDTH || grid.height() > TILE_HEIGHT {
+                return Err(String::from("the text is outside of the screen"));
+            }
+            Command::Cp437Data(Origin(0, 0), grid)
+        }
+        "brightness" => {
+            let value: u8 = std::str::from_utf8(payload)
+                .ok()
+                .and_then(|text| text.trim().parse().ok())
+                .ok_or("expected a number from 0 to 11")?;
+            let brightness = Brightness::new(value)
+                .ok_or_else(|| BrightnessOutOfRange(value).to_string())?;
+            Command::Brightness(brightness)
+        }
+        "clear" => Command::Clear,
+        "raw" => {
+            let bytes = decode_hex(payload).unwrap_or_else(|| payload.to_vec());
+            return Packet::try_from(bytes.as_slice())
+                .map_err(|()| String::from("too short for a packet header"));
+        }
+        _ => return Err(format!("unknown topic {topic}")),
+    };
+    Ok(command.into())
+}
+
+/// Reads hex digits, ignoring whitespace and colons.
+///
+/// returns: `None` if the payload is not text made of hex digits. Binary packets never are,
+/// as their first byte is zero.
+fn decode_hex(payload: &[u8]) -> Option<Vec<u8>> {
+    let digits: Vec<u8> = payload
+        .iter()
+        .copied()
+        .filter(|byte| !byte.is_ascii_whitespace() && *byte != b':')
+        .collect();
+    if digits.is_empty() || digits.len() % 2 == 1 {
+        return None;
+    }
+    digits
+        .chunks(2)
+        .map(|pair| {
+            let pair = std::str::from_utf8(pair).ok()?;
+            u8::from_str_radix(pair, 16).ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use servicepoint2::{Command, Grid, Origin, Packet};
+
+    use super::translate;
+
+    fn command(topic: &str, payload: &[u8]) -> Command {
+        Command::try_from(translate(topic, payload).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn text() {
+        let Command::Cp437Data(Origin(0, 0), grid) =
+            command("text", "ab\n☺".as_bytes())
+        else {
+            panic!("expected text");
+        };
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert_eq!(grid.get(0, 1), 0x01);
+        assert_eq!(grid.get(1, 1), b' ');
+
+        assert!(translate("text", "€".as_bytes()).is_err());
+        assert!(translate("text", "a".repeat(56).as_bytes()).is_ok());
+        assert!(translate("text", "a".repeat(57).as_bytes()).is_err());
+        assert!(translate("text", "a\n".repeat(21).as_bytes()).is_err());
+    }
+
+    #[test]
+    fn brightness() {
+        let Command::Brightness(brightness) = command("brightness", b" 7\n")
+        else {
+            panic!("expected brightness");
+        };
+        assert_eq!(u8::from(brightness), 7);
+
+        let error = translate("brightness", b"12").unwrap_err();
+        assert!(error.contains("maximum of 11"));
+        assert!(translate("brightness", b"bright").is_err());
+    }
+
+    #[test]
+    fn raw() {
+        let clear: Vec<u8> = Packet::from(Command::Clear).into();
+        assert_eq!(command("raw", &clear), Command::Clear);
+        assert_eq!(command("raw", b"0002 0000 0000 0000 0000"), Command::Clear);
+        assert!(translate("raw", b"0002").is_err());
+    }
+
+    #[test]
+    fn other_topics() {
+        assert_eq!(command("clear", b"anything"), Command::Clear);
+        assert!(translate("status", b"").is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use clap::Parser;
+use log::{error, info};
+
+use servicepoint2::{
+    Command, CompressionCode, Connection, Origin, PixelGrid, TerminalPreview,
+    Transition, FRAME_PACING,
+};
+
+use crate::playlist::{Playlist, Scene};
+
+mod playlist;
+
+/// How often the playlist file is checked for changes
+const RELOAD_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Parser, Debug)]
+struct Cli {
+    /// the playlist in TOML or JSON format
+    file: PathBuf,
+    #[arg(short, long, default_value = "localhost:2342")]
+    destination: String,
+    /// only check the playlist for errors
+    #[arg(long)]
+    check: bool,
+    /// play the playlist once instead of looping
+    #[arg(long)]
+    once: bool,
+    /// also draw what is sent in the terminal
+    #[arg(short, long)]
+    preview: bool,
+}
+
+/// The playlist file and when it was last changed
+struct Source {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl Source {
+    /// Returns true once after the file has been changed.
+    fn changed(&mut self) -> bool {
+        let modified = modified(&self.path);
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        true
+    }
+}
+
+/// example: `cargo run --bin playlist -- examples/playlist/lobby.toml`
+fn main() {
+    env_logger::init();
+    let cli = Cli::parse();
+
+    let mut source = Source {
+        modified: modified(&cli.file),
+        path: cli.file,
+    };
+    let mut playlist = match Playlist::load(&source.path) {
+        Ok(playlist) => playlist,
+        Err(err) => {
+            error!("invalid playlist {}: {err}", source.path.display());
+            exit(1);
+        }
+    };
+    info!("loaded {} scene(s)", playlist.scenes.len());
+    if cli.check {
+        println!("{} is valid", source.path.display());
+        return;
+    }
+
+    let mut connection =
+        Connection::open(&cli.destination).expect("could not open connection");
+    if cli.preview {
+        TerminalPreview::stdout().attach(&mut connection);
+    }
+
+    connection.send(Command::Clear.into()).expect("send failed");
+    let mut on_screen = PixelGrid::max_sized();
+    'playing: loop {
+        for scene in &playlist.scenes {
+            info!("showing {}", scene.name);
+            show(&connection, &on_screen, scene);
+            on_screen = scene.pixels.clone();
+
+            let until = Instant::now() + scene.duration;
+            while let Some(remaining) =
+                until.checked_duration_since(Instant::now())
+            {
+                thread::sleep(remaining.min(RELOAD_INTERVAL));
+                if !source.changed() {
+                    continue;
+                }
+                match Playlist::load(&source.path) {
+                    Ok(reloaded) => {
+                        info!(
+                            "reloaded playlist with {} scene(s)",
+                            reloaded.scenes.len()
+                        );
+                        playlist = reloaded;
+                        continue 'playing;
+                    }
+                    Err(err) => {
+                        error!("not reloading invalid playlist: {err}");
+                    }
+                }
+            }
+        }
+        if cli.once {
+            break;
+        }
+    }
+}
+
+/// Shows the scene, using its transition from what is currently on screen.
+fn show(connection: &Connection, on_screen: &PixelGrid, scene: &Scene) {
+    match scene.transition {
+        None => {
+            let command = Command::BitmapLinearWin(
+                Origin(0, 0),
+                scene.pixels.clone(),
+                CompressionCode::Lzma,
+            );
+            connection.send(command.into()).expect("send failed");
+        }
+        Some((effect, frames)) => {
+            let mut transition = Transition::new(
+                on_screen.clone(),
+                scene.pixels.clone(),
+                effect,
+                frames,
+            );
+            transition.set_compression(CompressionCode::Lzma);
+            for command in transition {
+                connection.send(command.into()).expect("send failed");
+                thread::sleep(FRAME_PACING);
+            }
+        }
+    }
+
+    for command in &scene.commands {
+        connection
+            .send(command.clone().into())
+            .expect("send failed");
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
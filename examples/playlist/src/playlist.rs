@@ -0,0 +1,448 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use servicepoint2::{
+    draw_text, load_image, Brightness, Command, Direction, Dithering, Grid,
+    ImageOptions, PixelGrid, Resize, TransitionEffect, GLYPH_HEIGHT,
+    PIXEL_HEIGHT, PIXEL_WIDTH,
+};
+
+/// The file as written by hand, see `lobby.toml` for an example
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct PlaylistFile {
+    /// used for scenes without their own transition
+    #[serde(default)]
+    transition: TransitionSpec,
+    scenes: Vec<SceneSpec>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct SceneSpec {
+    /// only used in log messages, defaults to the position
+    name: Option<String>,
+    /// seconds the scene stays on screen after the transition
+    duration: f64,
+    transition: Option<TransitionSpec>,
+    items: Vec<Item>,
+}
+
+/// Something shown in a scene. Items are applied in order.
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum Item {
+    /// turns all pixels on or off
+    Fill { on: bool },
+    /// text in the built-in font, lines are separated by `\n`
+    Text {
+        text: String,
+        #[serde(default)]
+        x: usize,
+        #[serde(default)]
+        y: usize,
+    },
+    /// an image file scaled to the whole screen. Relative paths start at the playlist.
+    Image {
+        path: PathBuf,
+        #[serde(default)]
+        resize: Resize,
+        #[serde(default)]
+        dithering: Dithering,
+        #[serde(default)]
+        invert: bool,
+    },
+    /// the brightness of the whole screen
+    Brightness { value: Brightness },
+    /// any command, sent as is after the pixels
+    Command { command: Command },
+}
+
+#[derive(Deserialize, Debug, Default, Clone, Copy)]
+#[serde(tag = "effect", rename_all = "snake_case", deny_unknown_fields)]
+enum TransitionSpec {
+    /// the new scene replaces the old one immediately
+    #[default]
+    Cut,
+    Wipe {
+        direction: DirectionSpec,
+        frames: usize,
+    },
+    Dissolve {
+        #[serde(default)]
+        seed: u64,
+        frames: usize,
+    },
+    Slide {
+        direction: DirectionSpec,
+        frames: usize,
+    },
+    Fade {
+        frames: usize,
+    },
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum DirectionSpec {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// A validated playlist with all images loaded
+#[derive(Debug)]
+pub struct Playlist {
+    pub scenes: Vec<Scene>,
+}
+
+/// A scene ready to be shown
+#[derive(Debug)]
+pub struct Scene {
+    pub name: String,
+    pub duration: Duration,
+    /// the effect and number of frames, `None` for a cut
+    pub transition: Option<(TransitionEffect, usize)>,
+    pub pixels: PixelGrid,
+    /// sent after the pixels, e.g. brightness changes
+    pub commands: Vec<Command>,
+}
+
+#[derive(Debug)]
+pub enum PlaylistError {
+    Read(PathBuf, std::io::Error),
+    Parse(String),
+    NoScenes,
+    /// a scene with its index and the problem
+    Scene(usize, String),
+}
+
+impl Display for PlaylistError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlaylistError::Read(path, err) => {
+                write!(f, "could not read {}: {err}", path.display())
+            }
+            PlaylistError::Parse(message) => write!(f, "{message}"),
+            PlaylistError::NoScenes => write!(f, "the playlist has no scenes"),
+            PlaylistError::Scene(index, message) => {
+                write!(f, "scene {}: {message}", index + 1)
+            }
+        }
+    }
+}
+
+impl Playlist {
+    /// Reads, validates and prepares a playlist.
+    ///
+    /// Files ending in `.json` are parsed as JSON, everything else as TOML.
+    pub fn load(path: &Path) -> Result<Playlist, PlaylistError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| PlaylistError::Read(path.to_path_buf(), err))?;
+        let file: PlaylistFile =
+            if path.extension().is_some_and(|ext| ext == "json") {
+                serde_json::from_str(&content)
+                    .map_err(|err| PlaylistError::Parse(err.to_string()))?
+            } else {
+                toml::from_str(&content)
+                    .map_err(|err| PlaylistError::Parse(err.to_string()))?
+            };
+
+        if file.scenes.is_empty() {
+            return Err(PlaylistError::NoScenes);
+        }
+        let base = path.parent().unwrap_or(Path::new("."));
+        let scenes = file
+            .scenes
+            .into_iter()
+            .enumerate()
+            .map(|(index, scene)| {
+                Scene::prepare(scene, index, file.transition, base)
+                    .map_err(|message| PlaylistError::Scene(index, message))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Playlist { scenes })
+    }
+}
+
+impl Scene {
+    fn prepare(
+        spec: SceneSpec,
+        index: usize,
+        default_transition: TransitionSpec,
+        base: &Path,
+    ) -> Result<Scene, String> {
+        let duration =
+            Duration::try_from_secs_f64(spec.duration).map_err(|err| {
+                format!("invalid duration {}: {err}", spec.duration)
+            })?;
+        if duration.is_zero() {
+            return Err(format!(
+                "duration has to be positive, got {}",
+                spec.duration
+            ));
+        }
+
+        let mut pixels = PixelGrid::max_sized();
+        let mut commands = vec![];
+        let mut brightness = Brightness::MAX;
+        for item in spec.items {
+            match item {
+                Item::Fill { on } => pixels.fill(on),
+                Item::Text { text, x, y } => {
+                    if x >= PIXEL_WIDTH || y >= PIXEL_HEIGHT {
+                        return Err(format!(
+                            "text at ({x}, {y}) starts outside of the screen"
+                        ));
+                    }
+                    for (line, text) in text.lines().enumerate() {
+                        let line_y = y + line * (GLYPH_HEIGHT + 1);
+                        draw_text(
+                            &mut pixels,
+                            x as isize,
+                            line_y as isize,
+                            text,
+                        );
+                    }
+                }
+                Item::Image {
+                    path,
+                    resize,
+                    dithering,
+                    invert,
+                } => {
+                    let options = ImageOptions {
+                        resize,
+                        dithering,
+                        invert,
+                        ..ImageOptions::default()
+                    };
+                    let path = base.join(path);
+                    pixels = load_image(&path, &options).map_err(|err| {
+                        format!("could not load {}: {err}", path.display())
+                    })?;
+                }
+                Item::Brightness { value } => {
+                    brightness = value;
+                    commands.push(Command::Brightness(value));
+                }
+                Item::Command { command } => commands.push(command),
+            }
+        }
+
+        let transition = match spec.transition.unwrap_or(default_transition) {
+            TransitionSpec::Cut => None,
+            TransitionSpec::Wipe { direction, frames } => {
+                Some((TransitionEffect::Wipe(direction.into()), frames))
+            }
+            TransitionSpec::Dissolve { seed, frames } => {
+                Some((TransitionEffect::Dissolve(seed), frames))
+            }
+            TransitionSpec::Slide { direction, frames } => {
+                Some((TransitionEffect::Slide(direction.into()), frames))
+            }
+            TransitionSpec::Fade { frames } => {
+                if frames < 3 {
+                    return Err(String::from(
+                        "fading needs at least three frames",
+                    ));
+                }
+                Some((TransitionEffect::Fade(brightness), frames))
+            }
+        };
+        if let Some((_, 0)) = transition {
+            return Err(String::from("a transition needs at least one frame"));
+        }
+
+        Ok(Scene {
+            name: spec.name.unwrap_or_else(|| format!("scene {}", index + 1)),
+            duration,
+            transition,
+            pixels,
+            commands,
+        })
+    }
+}
+
+impl From<DirectionSpec> for Direction {
+    fn from(direction: DirectionSpec) -> Self {
+        match direction {
+            DirectionSpec::Left => Direction::Left,
+            DirectionSpec::Right => Direction::Right,
+            DirectionSpec::Up => Direction::Up,
+            DirectionSpec::Down => Direction::Down,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prepare(scene: &str) -> Result<Scene, String> {
+        let spec: SceneSpec = toml::from_str(scene).unwrap();
+        Scene::prepare(spec, 0, TransitionSpec::Cut, Path::new("."))
+    }
+
+    fn write_playlist(name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir()
+            .join(format!("playlist-{}-{name}", std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn lobby() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("lobby.toml");
+        let playlist = Playlist::load(&path).unwrap();
+        let names: Vec<_> =
+            playlist.scenes.iter().map(|scene| &*scene.name).collect();
+        assert_eq!(names, ["welcome", "inverted", "dimmed", "raw commands"]);
+
+        let welcome = &playlist.scenes[0];
+        assert_eq!(welcome.duration, Duration::from_secs(10));
+        assert!(matches!(
+            welcome.transition,
+            Some((TransitionEffect::Wipe(Direction::Right), 20))
+        ));
+        assert_eq!(
+            welcome.commands,
+            [Command::Brightness(Brightness::new(11).unwrap())]
+        );
+
+        let dimmed = &playlist.scenes[2];
+        assert!(matches!(
+            dimmed.transition,
+            Some((TransitionEffect::Fade(brightness), 20))
+                if brightness == Brightness::new(5).unwrap()
+        ));
+
+        let raw = &playlist.scenes[3];
+        assert!(raw.transition.is_none());
+        assert!(matches!(raw.commands[..], [Command::Cp437Data(_, _)]));
+    }
+
+    #[test]
+    fn json() {
+        let path = write_playlist(
+            "scenes.json",
+            r#"{"scenes": [{"duration": 1.5, "items": [{"type": "fill", "on": true}]}]}"#,
+        );
+        let playlist = Playlist::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let scene = &playlist.unwrap().scenes[0];
+        assert_eq!(scene.name, "scene 1");
+        assert_eq!(scene.duration, Duration::from_millis(1500));
+        assert!(scene.pixels.get(0, 0));
+    }
+
+    #[test]
+    fn invalid_playlists() {
+        let path = write_playlist("empty.toml", "scenes = []");
+        let result = Playlist::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(PlaylistError::NoScenes)));
+
+        let path = write_playlist("unknown.toml", "scene = []");
+        let result = Playlist::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(PlaylistError::Parse(_))));
+
+        let path = write_playlist(
+            "zero.toml",
+            "[[scenes]]\nduration = 1\nitems = []\n\
+             [[scenes]]\nduration = 0\nitems = []",
+        );
+        let result = Playlist::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(PlaylistError::Scene(1, _))));
+
+        let path = std::env::temp_dir().join("playlist-does-not-exist.toml");
+        assert!(matches!(
+            Playlist::load(&path),
+            Err(PlaylistError::Read(_, _))
+        ));
+    }
+
+    #[test]
+    fn durations() {
+        let scene = prepare("duration = 0.25\nitems = []").unwrap();
+        assert_eq!(scene.duration, Duration::from_millis(250));
+
+        for duration in ["0", "-1", "nan", "inf", "1e300"] {
+            let scene = format!("duration = {duration}\nitems = []");
+            assert!(prepare(&scene).is_err(), "{duration}");
+        }
+    }
+
+    #[test]
+    fn items() {
+        let scene = prepare(
+            r#"
+            duration = 1
+            items = [
+                { type = "fill", on = true },
+                { type = "brightness", value = 3 },
+                { type = "command", command = "Clear" },
+            ]
+            "#,
+        )
+        .unwrap();
+        assert!(scene.pixels.get(PIXEL_WIDTH - 1, PIXEL_HEIGHT - 1));
+        assert_eq!(
+            scene.commands,
+            [
+                Command::Brightness(Brightness::new(3).unwrap()),
+                Command::Clear
+            ]
+        );
+
+        let error = prepare(
+            r#"
+            duration = 1
+            items = [{ type = "text", text = "x", x = 9999 }]
+            "#,
+        )
+        .unwrap_err();
+        assert!(error.contains("outside of the screen"), "{error}");
+
+        let error = prepare(
+            r#"
+            duration = 1
+            items = [{ type = "image", path = "does-not-exist.png" }]
+            "#,
+        )
+        .unwrap_err();
+        assert!(error.contains("does-not-exist.png"), "{error}");
+    }
+
+    #[test]
+    fn transitions() {
+        let scene = prepare(
+            r#"
+            duration = 1
+            items = []
+            transition = { effect = "slide", direction = "up", frames = 4 }
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            scene.transition,
+            Some((TransitionEffect::Slide(Direction::Up), 4))
+        ));
+
+        for transition in [
+            r#"{ effect = "fade", frames = 2 }"#,
+            r#"{ effect = "dissolve", frames = 0 }"#,
+        ] {
+            let scene =
+                format!("duration = 1\nitems = []\ntransition = {transition}");
+            assert!(prepare(&scene).is_err(), "{transition}");
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use std::collections::{HashMap, VecDeque};
+use std::io::ErrorKind;
+use std::net::{SocketAddr, UdpSocket};
+use std::process::exit;
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use log::{debug, info, warn};
+
+use servicepoint2::{
+    Brightness, Command, CommandKind, Connection, Packet, Policy, FRAME_PACING,
+};
+
+use crate::region::{parse_columns, Region};
+
+mod region;
+
+/// Packets waiting per client. When a client sends faster than its share of
+/// the frame rate, its oldest packets are dropped.
+const MAX_QUEUED: usize = 32;
+
+#[derive(Parser, Debug)]
+struct Cli {
+    /// address to receive packets from clients on
+    #[arg(short, long, default_value = "0.0.0.0:2342")]
+    listen: String,
+    /// the real display
+    #[arg(short, long, default_value = "172.23.42.29:2342")]
+    destination: String,
+    /// screen region in tiles as x,y,width,height. Clients get the next free
+    /// region when they send their first packet.
+    #[arg(short, long)]
+    region: Vec<Region>,
+    /// split the screen into this many columns if no regions are specified
+    #[arg(short, long, default_value_t = 2, value_parser = parse_columns)]
+    columns: usize,
+    /// instead of regions, give every client the whole screen for this many
+    /// seconds in turn. Packets of the other clients are dropped meanwhile.
+    #[arg(short, long, value_parser = parse_time_slot)]
+    time_slot: Option<Duration>,
+    /// seconds without packets after which a client loses its region
+    #[arg(long, default_value = "10", value_parser = parse_seconds)]
+    timeout: Duration,
+    /// maximum packets per second and client
+    #[arg(long)]
+    max_rate: Option<f64>,
+    /// brightness values of clients are lowered to this
+    #[arg(long)]
+    max_brightness: Option<u8>,
+}
+
+struct Client {
+    region: Region,
+    last_seen: Instant,
+    queue: VecDeque<Packet>,
+}
+
+struct Proxy {
+    regions: Vec<Region>,
+    time_slot: Option<Duration>,
+    timeout: Duration,
+    clients: HashMap<SocketAddr, Client>,
+    /// clients in the order they joined, used for taking turns
+    order: Vec<SocketAddr>,
+    /// index into `order` of the client that sends next or owns the screen
+    turn: usize,
+    slot_start: Instant,
+    /// packets of the proxy itself, which are sent before any client packets
+    own_queue: VecDeque<Packet>,
+    policy: Policy<SocketAddr>,
+}
+
+fn parse_seconds(arg: &str) -> Result<Duration, String> {
+    let seconds: f64 = arg.parse().map_err(|_| "expected seconds")?;
+    Duration::try_from_secs_f64(seconds).map_err(|err| err.to_string())
+}
+
+fn parse_time_slot(arg: &str) -> Result<Duration, String> {
+    let time_slot = parse_seconds(arg)?;
+    if time_slot.is_zero() {
+        return Err(String::from("a time slot has to be longer than zero"));
+    }
+    Ok(time_slot)
+}
+
+/// example: `cargo run --bin servicepoint-proxy -- --destination 172.23.42.29:2342 --columns 3`,
+/// then point up to three clients at port 2342 of this machine
+fn main() {
+    env_logger::init();
+    let cli = Cli::parse();
+    assert!(cli.columns > 0, "need at least one column");
+
+    let socket = UdpSocket::bind(&cli.listen).expect("could not bind socket");
+    let connection = Connection::open(&cli.destination)
+        .expect("could not connect to display");
+
+    let regions = match (&cli.time_slot, cli.region.is_empty()) {
+        (Some(_), _) => vec![Region::FULL_SCREEN],
+        (None, true) => Region::columns(cli.columns),
+        (None, false) => cli.region.clone(),
+    };
+    let mut policy = Policy::new()
+        .deny(CommandKind::HardReset)
+        .deny(CommandKind::FadeOut)
+        .deny(CommandKind::BitmapLegacy);
+    if let Some(max_rate) = cli.max_rate {
+        policy =
+            policy
+                .max_packets_per_second(max_rate)
+                .unwrap_or_else(|err| {
+                    eprintln!("{err}");
+                    exit(1);
+                });
+    }
+    if let Some(max_brightness) = cli.max_brightness {
+        let max_brightness = Brightness::saturating_from(max_brightness);
+        policy = policy.brightness_range(Brightness::MIN..=max_brightness);
+    }
+
+    let mut proxy = Proxy {
+        regions,
+        time_slot: cli.time_slot,
+        timeout: cli.timeout,
+        clients: HashMap::new(),
+        order: vec![],
+        turn: 0,
+        slot_start: Instant::now(),
+        own_queue: VecDeque::new(),
+        policy,
+    };
+
+    let mut buffer = [0u8; u16::MAX as usize];
+    let mut next_frame = Instant::now();
+    loop {
+        let now = Instant::now();
+        if now >= next_frame {
+            proxy.expire_clients(now);
+            proxy.rotate_slot(now);
+            if let Some(packet) = proxy.next_packet() {
+                if let Err(err) = connection.send(packet) {
+                    warn!("could not send to display: {err}");
+                }
+            }
+            next_frame = now + FRAME_PACING;
+        }
+
+        // a zero timeout is not allowed, it would mean blocking forever
+        let wait = next_frame.saturating_duration_since(Instant::now());
+        socket
+            .set_read_timeout(Some(wait.max(Duration::from_millis(1))))
+            .unwrap();
+        let (size, source) = match socket.recv_from(&mut buffer) {
+            Ok(received) => received,
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    ErrorKind::WouldBlock | ErrorKind::TimedOut
+                ) =>
+            {
+                continue
+            }
+            Err(err) => panic!("could not receive: {err}"),
+        };
+        proxy.receive(source, &buffer[..size], Instant::now());
+    }
+}
+
+impl Proxy {
+    fn receive(&mut self, source: SocketAddr, data: &[u8], now: Instant) {
+        let command = match Packet::try_from(data) {
+            Err(()) => {
+                warn!("ignoring {} bytes from {source}", data.len());
+                return;
+            }
+            Ok(packet) => match Command::try_from(packet) {
+                Err(err) => {
+                    warn!("ignoring invalid packet from {source}: {err:?}");
+                    return;
+                }
+                Ok(command) => command,
+            },
+        };
+
+        if !self.clients.contains_key(&source) && !self.join(source, now) {
+            debug!("no region left for {source}, dropping {command}");
+            return;
+        }
+
+        let is_active = self.time_slot.is_none()
+            || self.order.get(self.turn) == Some(&source);
+        let client = self.clients.get_mut(&source).unwrap();
+        client.last_seen = now;
+        if !is_active {
+            debug!("{source} does not have the screen, dropping {command}");
+            return;
+        }
+
+        let command = match self.policy.check(source, command) {
+            Ok(command) => command,
+            Err(rejection) => {
+                warn!("blocked command from {source}: {rejection}");
+                return;
+            }
+        };
+        let commands = match client.region.translate(command) {
+            Ok(commands) => commands,
+            Err(reason) => {
+                warn!("blocked command from {source}: {reason}");
+                return;
+            }
+        };
+        for command in commands {
+            if client.queue.len() >= MAX_QUEUED {
+                client.queue.pop_front();
+                debug!("{source} is sending too fast, dropped a packet");
+            }
+            client.queue.push_back(command.into());
+        }
+    }
+
+    /// Registers a new client.
+    ///
+    /// returns: false if no region is free
+    fn join(&mut self, source: SocketAddr, now: Instant) -> bool {
+        let region = if self.time_slot.is_some() {
+            Region::FULL_SCREEN
+        } else {
+            let free = self.regions.iter().find(|region| {
+                !self
+                    .clients
+                    .values()
+                    .any(|client| client.region == **region)
+            });
+            match free {
+                None => return false,
+                Some(region) => *region,
+            }
+        };
+
+        info!("{source} joined with region {region:?}");
+        self.clients.insert(
+            source,
+            Client {
+                region,
+                last_seen: now,
+                queue: VecDeque::new(),
+            },
+        );
+        self.order.push(source);
+        true
+    }
+
+    /// Removes clients that did not send anything for a while and clears
+    /// their region.
+    fn expire_clients(&mut self, now: Instant) {
+        let expired: Vec<SocketAddr> = self
+            .clients
+            .iter()
+            .filter(|(_, client)| now - client.last_seen > self.timeout)
+            .map(|(source, _)| *source)
+            .collect();
+
+        for source in expired {
+            let client = self.clients.remove(&source).unwrap();
+            self.policy.forget(&source);
+            info!("{source} timed out, freeing region {:?}", client.region);
+
+            let index = self.order.iter().position(|s| *s == source).unwrap();
+            self.order.remove(index);
+            let had_screen = self.time_slot.is_none() || index == self.turn;
+            if had_screen {
+                self.clear(client.region);
+                self.slot_start = now;
+            }
+            if index < self.turn {
+                self.turn -= 1;
+            }
+            if self.turn >= self.order.len() {
+                self.turn = 0;
+            }
+        }
+    }
+
+    /// In time slot mode, passes the screen on to the next client when the
+    /// current slot is over.
+    fn rotate_slot(&mut self, now: Instant) {
+        let Some(time_slot) = self.time_slot else {
+            return;
+        };
+        if self.order.len() < 2 || now - self.slot_start < time_slot {
+            return;
+        }
+
+        let previous = self.order[self.turn];
+        self.clients.get_mut(&previous).unwrap().queue.clear();
+        self.turn = (self.turn + 1) % self.order.len();
+        self.slot_start = now;
+        info!("{} now has the screen", self.order[self.turn]);
+        self.clear(Region::FULL_SCREEN);
+    }
+
+    fn clear(&mut self, region: Region) {
+        let commands = region
+            .translate(Command::Clear)
+            .expect("clear is always allowed");
+        self.own_queue
+            .extend(commands.into_iter().map(Packet::from));
+    }
+
+    /// Picks the packet to send in the current frame, letting the clients
+    /// take turns.
+    fn next_packet(&mut self) -> Option<Packet> {
+        if let Some(packet) = self.own_queue.pop_front() {
+            return Some(packet);
+        }
+        if self.time_slot.is_some() {
+            let source = self.order.get(self.turn)?;
+            return self.clients.get_mut(source)?.queue.pop_front();
+        }
+
+        for _ in 0..self.order.len() {
+            self.turn = (self.turn + 1) % self.order.len();
+            let source = self.order[self.turn];
+            let client = self.clients.get_mut(&source).unwrap();
+            if let Some(packet) = client.queue.pop_front() {
+                return Some(packet);
+            }
+        }
+        None
+    }
+}
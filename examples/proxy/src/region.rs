@@ -0,0 +1,320 @@
+use std::str::FromStr;
+
+use servicepoint2::{
+    ByteGrid, Command, CompressionCode, Grid, Origin, PixelGrid, TILE_HEIGHT,
+    TILE_SIZE, TILE_WIDTH,
+};
+
+/// A rectangular part of the screen, in tiles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Region {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Region {
+    pub const FULL_SCREEN: Region = Region {
+        x: 0,
+        y: 0,
+        width: TILE_WIDTH,
+        height: TILE_HEIGHT,
+    };
+
+    /// Splits the screen into columns of (almost) equal width.
+    ///
+    /// `count` has to be between 1 and `TILE_WIDTH`, see `parse_columns`.
+    pub fn columns(count: usize) -> Vec<Region> {
+        (0..count)
+            .map(|index| {
+                let start = index * TILE_WIDTH / count;
+                let end = (index + 1) * TILE_WIDTH / count;
+                Region {
+                    x: start,
+                    y: 0,
+                    width: end - start,
+                    height: TILE_HEIGHT,
+                }
+            })
+            .collect()
+    }
+
+    fn is_full_screen(&self) -> bool {
+        *self == Self::FULL_SCREEN
+    }
+
+    /// Moves a command sent by a client into this region.
+    ///
+    /// The client sees the region as its own screen starting at (0, 0).
+    /// Everything outside the region is cut off.
+    ///
+    /// returns: the commands to send to the display, which may be empty if
+    /// nothing is left after clipping, or the reason the command is blocked
+    pub fn translate(&self, command: Command) -> Result<Vec<Command>, String> {
+        let commands = match command {
+            Command::HardReset | Command::FadeOut => {
+                return Err(format!("{command} is not allowed"))
+            }
+            #[allow(deprecated)]
+            Command::BitmapLegacy => {
+                return Err(format!("{command} is not allowed"))
+            }
+            Command::BitmapLinear(..)
+            | Command::BitmapLinearAnd(..)
+            | Command::BitmapLinearOr(..)
+            | Command::BitmapLinearXor(..)
+                if self.is_full_screen() =>
+            {
+                vec![command]
+            }
+            Command::BitmapLinear(..)
+            | Command::BitmapLinearAnd(..)
+            | Command::BitmapLinearOr(..)
+            | Command::BitmapLinearXor(..) => {
+                return Err(format!(
+                    "{command} can only be used with the whole screen"
+                ))
+            }
+            Command::Clear if self.is_full_screen() => vec![Command::Clear],
+            Command::Clear => vec![Command::BitmapLinearWin(
+                self.pixel_origin(),
+                PixelGrid::new(self.width * TILE_SIZE, self.height * TILE_SIZE),
+                CompressionCode::Uncompressed,
+            )],
+            Command::Brightness(brightness) if self.is_full_screen() => {
+                vec![Command::Brightness(brightness)]
+            }
+            Command::Brightness(brightness) => {
+                let mut grid = ByteGrid::new(self.width, self.height);
+                grid.fill(brightness.into());
+                vec![Command::CharBrightness(self.tile_origin(), grid)]
+            }
+            Command::CharBrightness(origin, grid) => self
+                .clip_tiles(origin, &grid)
+                .map(|(origin, grid)| Command::CharBrightness(origin, grid))
+                .into_iter()
+                .collect(),
+            Command::Cp437Data(origin, grid) => self
+                .clip_tiles(origin, &grid)
+                .map(|(origin, grid)| Command::Cp437Data(origin, grid))
+                .into_iter()
+                .collect(),
+            Command::BitmapLinearWin(origin, pixels, compression) => self
+                .clip_pixels(origin, &pixels)
+                .map(|(origin, pixels)| {
+                    Command::BitmapLinearWin(origin, pixels, compression)
+                })
+                .into_iter()
+                .collect(),
+        };
+        Ok(commands)
+    }
+
+    fn tile_origin(&self) -> Origin {
+        Origin(self.x, self.y)
+    }
+
+    fn pixel_origin(&self) -> Origin {
+        Origin(self.x * TILE_SIZE, self.y * TILE_SIZE)
+    }
+
+    fn clip_tiles(
+        &self,
+        origin: Origin,
+        grid: &ByteGrid,
+    ) -> Option<(Origin, ByteGrid)> {
+        let Origin(x, y) = origin;
+        let w = grid.width().min(self.width.saturating_sub(x));
+        let h = grid.height().min(self.height.saturating_sub(y));
+        if w == 0 || h == 0 {
+            return None;
+        }
+        Some((self.tile_origin() + origin, grid.window(0, 0, w, h)))
+    }
+
+    fn clip_pixels(
+        &self,
+        origin: Origin,
+        pixels: &PixelGrid,
+    ) -> Option<(Origin, PixelGrid)> {
+        let Origin(x, y) = origin;
+        // the window is tile aligned in x-direction, so the width stays a
+        // multiple of the tile size
+        let w = pixels
+            .width()
+            .min((self.width * TILE_SIZE).saturating_sub(x));
+        let h = pixels
+            .height()
+            .min((self.height * TILE_SIZE).saturating_sub(y));
+        if w == 0 || h == 0 {
+            return None;
+        }
+        Some((self.pixel_origin() + origin, pixels.window(0, 0, w, h)))
+    }
+}
+
+/// Parses the number of columns, each of which has to be at least one tile wide.
+pub fn parse_columns(arg: &str) -> Result<usize, String> {
+    let count: usize = arg
+        .parse()
+        .map_err(|err| format!("invalid number of columns: {err}"))?;
+    if count == 0 || count > TILE_WIDTH {
+        return Err(format!(
+            "the number of columns has to be between 1 and {TILE_WIDTH}"
+        ));
+    }
+    Ok(count)
+}
+
+impl FromStr for Region {
+    type Err = String;
+
+    /// Parses `x,y,width,height` in tiles.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s
+            .split(',')
+            .map(|part| part.trim().parse::<usize>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("invalid number in region: {err}"))?;
+        let [x, y, width, height] = parts[..] else {
+            return Err("expected x,y,width,height".to_string());
+        };
+
+        if width == 0 || height == 0 {
+            return Err("region cannot be empty".to_string());
+        }
+        if x + width > TILE_WIDTH || y + height > TILE_HEIGHT {
+            return Err(format!(
+                "region does not fit on the screen of {TILE_WIDTH}x{TILE_HEIGHT} tiles"
+            ));
+        }
+        Ok(Region {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use servicepoint2::{
+        Brightness, ByteGrid, Command, CompressionCode, Grid, Origin,
+        PixelGrid, TILE_HEIGHT, TILE_WIDTH,
+    };
+
+    use super::{parse_columns, Region};
+
+    const RIGHT_HALF: Region = Region {
+        x: 28,
+        y: 0,
+        width: 28,
+        height: TILE_HEIGHT,
+    };
+
+    #[test]
+    fn columns() {
+        let columns = Region::columns(3);
+        assert_eq!(
+            columns.iter().map(|r| (r.x, r.width)).collect::<Vec<_>>(),
+            [(0, 18), (18, 19), (37, 19)]
+        );
+        assert_eq!(Region::columns(1), [Region::FULL_SCREEN]);
+        assert!(Region::columns(TILE_WIDTH).iter().all(|r| r.width == 1));
+    }
+
+    #[test]
+    fn column_count() {
+        assert_eq!(parse_columns("4"), Ok(4));
+        assert_eq!(parse_columns(&TILE_WIDTH.to_string()), Ok(TILE_WIDTH));
+        assert!(parse_columns("0").is_err());
+        assert!(parse_columns(&(TILE_WIDTH + 1).to_string()).is_err());
+        assert!(parse_columns("two").is_err());
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("28, 0, 28, 20".parse::<Region>(), Ok(RIGHT_HALF));
+        assert!("0,0,0,1".parse::<Region>().is_err());
+        assert!("50,0,10,1".parse::<Region>().is_err());
+        assert!("0,15,1,10".parse::<Region>().is_err());
+        assert!("0,0,1".parse::<Region>().is_err());
+        assert!("0,0,-1,1".parse::<Region>().is_err());
+    }
+
+    #[test]
+    fn blocked_commands() {
+        assert!(RIGHT_HALF.translate(Command::HardReset).is_err());
+        assert!(RIGHT_HALF.translate(Command::FadeOut).is_err());
+        let linear = Command::BitmapLinear(
+            0,
+            servicepoint2::BitVec::new(8),
+            CompressionCode::Uncompressed,
+        );
+        assert!(RIGHT_HALF.translate(linear.clone()).is_err());
+        assert_eq!(
+            Region::FULL_SCREEN.translate(linear.clone()),
+            Ok(vec![linear])
+        );
+    }
+
+    #[test]
+    fn clear_and_brightness() {
+        let commands = RIGHT_HALF.translate(Command::Clear).unwrap();
+        let [Command::BitmapLinearWin(origin, pixels, _)] = &commands[..]
+        else {
+            panic!("expected a window");
+        };
+        assert_eq!(*origin, Origin(28 * 8, 0));
+        assert_eq!((pixels.width(), pixels.height()), (28 * 8, 160));
+
+        let brightness = Brightness::try_from(5).unwrap();
+        let commands = RIGHT_HALF
+            .translate(Command::Brightness(brightness))
+            .unwrap();
+        let [Command::CharBrightness(Origin(28, 0), grid)] = &commands[..]
+        else {
+            panic!("expected char brightness");
+        };
+        assert_eq!((grid.width(), grid.height()), (28, TILE_HEIGHT));
+        assert_eq!(grid.get(27, 19), 5);
+    }
+
+    #[test]
+    fn clips_tiles() {
+        let text = ByteGrid::new(10, 2);
+        let commands = RIGHT_HALF
+            .translate(Command::Cp437Data(Origin(24, 19), text.clone()))
+            .unwrap();
+        let [Command::Cp437Data(Origin(52, 19), clipped)] = &commands[..]
+        else {
+            panic!("expected clipped text");
+        };
+        assert_eq!((clipped.width(), clipped.height()), (4, 1));
+
+        let outside = RIGHT_HALF
+            .translate(Command::CharBrightness(Origin(28, 0), text))
+            .unwrap();
+        assert!(outside.is_empty());
+    }
+
+    #[test]
+    fn clips_pixels() {
+        let pixels = PixelGrid::new(64, 16);
+        let commands = RIGHT_HALF
+            .translate(Command::BitmapLinearWin(
+                Origin(200, 150),
+                pixels,
+                CompressionCode::Uncompressed,
+            ))
+            .unwrap();
+        let [Command::BitmapLinearWin(Origin(424, 150), clipped, _)] =
+            &commands[..]
+        else {
+            panic!("expected clipped window");
+        };
+        assert_eq!((clipped.width(), clipped.height()), (24, 10));
+    }
+}
@@ -3,7 +3,7 @@ use std::time::Duration;
 use clap::Parser;
 use rand::Rng;
 
-use servicepoint2::Command::{BitmapLinearWin, Brightness, CharBrightness};
+use servicepoint2::Command::BitmapLinearWin;
 use servicepoint2::*;
 
 #[derive(Parser, Debug)]
@@ -14,6 +14,9 @@ struct Cli {
     enable_all: bool,
     #[arg(short, long, default_value_t = 100, allow_negative_numbers = false)]
     wait_ms: u64,
+    /// how many steps it takes to fade to the next brightness map
+    #[arg(short, long, default_value_t = 20)]
+    steps: usize,
 }
 
 fn main() {
@@ -33,31 +36,35 @@ fn main() {
         connection.send(command.into()).expect("send failed");
     }
 
-    // set all pixels to the same random brightness
+    // start with all tiles at the same random brightness
     let mut rng = rand::thread_rng();
-    connection.send(Brightness(rng.gen()).into()).unwrap();
+    let mut current = BrightnessMap::uniform(random_brightness(&mut rng));
+    connection.send(current.command().into()).unwrap();
 
-    // continuously update random windows to new random brightness
+    // continuously fade to new random brightness maps
     loop {
-        let min_size = 1;
-        let x = rng.gen_range(0..TILE_WIDTH - min_size);
-        let y = rng.gen_range(0..TILE_HEIGHT - min_size);
-
-        let w = rng.gen_range(min_size..=TILE_WIDTH - x);
-        let h = rng.gen_range(min_size..=TILE_HEIGHT - y);
-
-        let origin = Origin(x, y);
-        let mut luma = ByteGrid::new(w, h);
-
-        for y in 0..h {
-            for x in 0..w {
-                luma.set(x, y, rng.gen());
-            }
+        let target = random_map(&mut rng);
+        for map in current.fade(&target, cli.steps) {
+            connection.send(map.command().into()).unwrap();
+            std::thread::sleep(wait_duration);
         }
+        current = target;
+    }
+}
+
+fn random_brightness(rng: &mut impl Rng) -> Brightness {
+    Brightness::saturating_from(rng.gen_range(0..=u8::from(Brightness::MAX)))
+}
 
-        connection
-            .send(CharBrightness(origin, luma).into())
-            .unwrap();
-        std::thread::sleep(wait_duration);
+fn random_map(rng: &mut impl Rng) -> BrightnessMap {
+    let a = random_brightness(rng);
+    let b = random_brightness(rng);
+    match rng.gen_range(0..6) {
+        0 => BrightnessMap::uniform(a),
+        1 => BrightnessMap::horizontal_gradient(a, b),
+        2 => BrightnessMap::vertical_gradient(a, b),
+        3 => BrightnessMap::radial_gradient(a, b),
+        4 => BrightnessMap::vignette(a, b),
+        _ => BrightnessMap::noise(a, b, rng.gen()),
     }
 }
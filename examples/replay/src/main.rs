@@ -0,0 +1,152 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::net::UdpSocket;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clap::{Parser, Subcommand};
+use log::warn;
+
+use servicepoint2::{
+    Connection, Packet, Recorder, RecordingReader, RecordingWriter,
+};
+
+#[derive(Parser, Debug)]
+struct Cli {
+    #[command(subcommand)]
+    command: Mode,
+}
+
+#[derive(Subcommand, Debug)]
+enum Mode {
+    /// Listen for packets and record them until interrupted
+    Record {
+        file: PathBuf,
+        #[arg(short, long, default_value = "0.0.0.0:2342")]
+        listen: String,
+        /// also send the received packets to this address
+        #[arg(short, long)]
+        forward: Option<String>,
+    },
+    /// Send the packets of a recording with their original timing
+    Play {
+        file: PathBuf,
+        #[arg(short, long, default_value = "localhost:2342")]
+        destination: String,
+        /// playback speed, e.g. 2 for twice as fast
+        #[arg(short, long, default_value_t = 1.0, value_parser = parse_speed)]
+        speed: f64,
+        /// wait for enter before sending each packet instead of using the timing
+        #[arg(long)]
+        step: bool,
+        /// start over when the end of the recording is reached
+        #[arg(long = "loop")]
+        repeat: bool,
+    },
+}
+
+/// example: `cargo run --bin replay -- record demo.sp2rec --listen 0.0.0.0:2342`, then
+/// `cargo run --bin replay -- play demo.sp2rec --speed 2`
+fn main() {
+    env_logger::init();
+    match Cli::parse().command {
+        Mode::Record {
+            file,
+            listen,
+            forward,
+        } => record(file, &listen, forward),
+        Mode::Play {
+            file,
+            destination,
+            speed,
+            step,
+            repeat,
+        } => {
+            let connection = Connection::open(destination).unwrap();
+            loop {
+                play(&file, &connection, speed, step);
+                if !repeat {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+fn parse_speed(arg: &str) -> Result<f64, String> {
+    let speed: f64 = arg.parse().map_err(|_| "expected a number")?;
+    if speed.is_finite() && speed > 0.0 {
+        Ok(speed)
+    } else {
+        Err(String::from("has to be positive"))
+    }
+}
+
+fn record(file: PathBuf, listen: &str, forward: Option<String>) {
+    let socket = UdpSocket::bind(listen).expect("could not bind socket");
+    // not buffered so the recording stays valid when interrupted
+    let file = File::create(file).expect("could not create recording");
+    let writer = RecordingWriter::new(file).unwrap();
+    let mut recorder = match forward {
+        None => Recorder::offline(writer),
+        Some(forward) => {
+            Recorder::new(Connection::open(forward).unwrap(), writer)
+        }
+    };
+
+    let mut buffer = [0u8; u16::MAX as usize];
+    loop {
+        let (size, source) = socket.recv_from(&mut buffer).unwrap();
+        match Packet::try_from(&buffer[..size]) {
+            Err(()) => warn!("ignoring {size} bytes from {source}"),
+            Ok(packet) => {
+                println!("{source}: {packet}");
+                // the packet is recorded even if forwarding it fails
+                if let Err(err) = recorder.send(packet) {
+                    warn!("could not record or forward packet: {err}");
+                }
+            }
+        }
+    }
+}
+
+fn play(file: &Path, connection: &Connection, speed: f64, step: bool) {
+    let file = File::open(file).expect("could not open recording");
+    let reader = RecordingReader::new(BufReader::new(file)).unwrap();
+
+    let start = Instant::now();
+    for (index, record) in reader.enumerate() {
+        let (timestamp, packet) = match record {
+            Ok(record) => record,
+            Err(err) => {
+                warn!("stopping at damaged record: {err}");
+                break;
+            }
+        };
+
+        if step {
+            println!("press enter to send #{} {packet}", index + 1);
+            std::io::stdin().read_line(&mut String::new()).unwrap();
+        } else {
+            let due =
+                Duration::try_from_secs_f64(timestamp.as_secs_f64() / speed)
+                    .ok()
+                    .and_then(|delay| start.checked_add(delay));
+            let Some(due) = due else {
+                warn!("stopping at a record too far in the future");
+                break;
+            };
+            thread::sleep(due.saturating_duration_since(Instant::now()));
+            println!(
+                "[{:>10.3}] #{} {packet}",
+                timestamp.as_secs_f64(),
+                index + 1
+            );
+        }
+
+        if let Err(err) = connection.send(packet) {
+            warn!("could not send: {err}");
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use std::path::PathBuf;
+use std::process::exit;
+
+use clap::{Parser, ValueEnum};
+use log::{error, info};
+
+use servicepoint2::{
+    load_frames, CompressionCode, Connection, Dithering, ImageOptions,
+    ImagePlayer, Resize, TerminalPreview,
+};
+
+#[derive(Parser, Debug)]
+struct Cli {
+    /// the PNG, JPEG or GIF file to show
+    file: PathBuf,
+    #[arg(short, long, default_value = "localhost:2342")]
+    destination: String,
+    /// how the image is scaled to the screen
+    #[arg(short, long, value_enum, default_value_t = ResizeArg::Fit)]
+    resize: ResizeArg,
+    /// how gray pixels are turned into on and off
+    #[arg(long, value_enum, default_value_t = DitheringArg::FloydSteinberg)]
+    dithering: DitheringArg,
+    /// brightness from 0 to 255 above which pixels are on, only without dithering
+    #[arg(short, long, default_value_t = 128)]
+    threshold: u8,
+    /// turn dark pixels on instead of bright ones
+    #[arg(short, long)]
+    invert: bool,
+    /// how often an animation is played
+    #[arg(long, default_value_t = 1)]
+    times: usize,
+    /// play an animation until interrupted
+    #[arg(long = "loop")]
+    repeat: bool,
+    /// also draw what is sent in the terminal
+    #[arg(short, long)]
+    preview: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum ResizeArg {
+    /// scale to fit completely, keeping the aspect ratio
+    Fit,
+    /// scale to cover everything, cutting off the overlapping parts
+    Fill,
+    /// scale to exactly the screen size, ignoring the aspect ratio
+    Stretch,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum DitheringArg {
+    /// every pixel brighter than the threshold is on
+    None,
+    /// a regular 4x4 pattern, looks calm in animations
+    Ordered,
+    /// error diffusion, shows the most detail in still images
+    FloydSteinberg,
+}
+
+/// example: `cargo run --bin show_image -- logo.gif --loop --dithering ordered`
+fn main() {
+    env_logger::init();
+    let cli = Cli::parse();
+
+    let options = ImageOptions {
+        resize: cli.resize.into(),
+        dithering: cli.dithering.into(),
+        threshold: cli.threshold,
+        invert: cli.invert,
+        ..ImageOptions::default()
+    };
+    let frames = match load_frames(&cli.file, &options) {
+        Ok(frames) => frames,
+        Err(err) => {
+            error!("could not load {}: {err}", cli.file.display());
+            exit(1);
+        }
+    };
+    info!("loaded {} frame(s)", frames.len());
+
+    let mut connection =
+        Connection::open(&cli.destination).expect("could not open connection");
+    if cli.preview {
+        TerminalPreview::stdout().attach(&mut connection);
+    }
+    let mut player = ImagePlayer::new(frames);
+    player.set_compression(CompressionCode::Lzma);
+    player.set_loops(if cli.repeat { None } else { Some(cli.times) });
+    player.play(&connection).expect("send failed");
+}
+
+impl From<ResizeArg> for Resize {
+    fn from(resize: ResizeArg) -> Self {
+        match resize {
+            ResizeArg::Fit => Resize::Fit,
+            ResizeArg::Fill => Resize::Fill,
+            ResizeArg::Stretch => Resize::Stretch,
+        }
+    }
+}
+
+impl From<DitheringArg> for Dithering {
+    fn from(dithering: DitheringArg) -> Self {
+        match dithering {
+            DitheringArg::None => Dithering::None,
+            DitheringArg::Ordered => Dithering::Ordered,
+            DitheringArg::FloydSteinberg => Dithering::FloydSteinberg,
+        }
+    }
+}
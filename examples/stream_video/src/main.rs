@@ -0,0 +1,169 @@
+use std::io::{ErrorKind, Read};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use clap::{Parser, ValueEnum};
+use image::{DynamicImage, GrayImage};
+use log::{error, info};
+
+use servicepoint2::{
+    convert_image, Animator, CompressionCode, Connection, Dithering,
+    ImageOptions, PixelGrid, Resize,
+};
+
+#[derive(Parser, Debug)]
+struct Cli {
+    #[arg(short, long, default_value = "localhost:2342")]
+    destination: String,
+    /// width of the incoming frames in pixels
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    width: u32,
+    /// height of the incoming frames in pixels
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    height: u32,
+    /// how the frames are scaled to the screen
+    #[arg(short, long, value_enum, default_value_t = ResizeArg::Fit)]
+    resize: ResizeArg,
+    /// how gray pixels are turned into on and off
+    #[arg(long, value_enum, default_value_t = DitheringArg::Ordered)]
+    dithering: DitheringArg,
+    /// brightness from 0 to 255 above which pixels are on, only without dithering
+    #[arg(short, long, default_value_t = 128)]
+    threshold: u8,
+    /// turn dark pixels on instead of bright ones
+    #[arg(short, long)]
+    invert: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum ResizeArg {
+    /// scale to fit completely, keeping the aspect ratio
+    Fit,
+    /// scale to cover everything, cutting off the overlapping parts
+    Fill,
+    /// scale to exactly the screen size, ignoring the aspect ratio
+    Stretch,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum DitheringArg {
+    /// every pixel brighter than the threshold is on
+    None,
+    /// a regular 4x4 pattern, looks calm in animations
+    Ordered,
+    /// error diffusion, shows the most detail in still images
+    FloydSteinberg,
+}
+
+/// The newest frame read from stdin that was not shown yet
+#[derive(Debug, Default)]
+struct Incoming {
+    frame: Option<Vec<u8>>,
+    received: u64,
+    /// frames that were replaced by a newer one before being shown
+    skipped: u64,
+    finished: bool,
+}
+
+/// Streams raw 8 bit grayscale frames from stdin to the display.
+///
+/// example for a video file:
+/// `ffmpeg -re -i video.mp4 -vf scale=448:-2 -f rawvideo -pix_fmt gray - | cargo run --bin stream_video -- --width 448 --height 252`
+///
+/// example for a part of the screen:
+/// `ffmpeg -f x11grab -video_size 896x320 -i :0.0+0,0 -f rawvideo -pix_fmt gray - | cargo run --bin stream_video -- --width 896 --height 320`
+fn main() {
+    env_logger::init();
+    let cli = Cli::parse();
+
+    let options = ImageOptions {
+        resize: cli.resize.into(),
+        dithering: cli.dithering.into(),
+        threshold: cli.threshold,
+        invert: cli.invert,
+        ..ImageOptions::default()
+    };
+
+    let connection =
+        Connection::open(&cli.destination).expect("could not open connection");
+
+    let incoming = Arc::new(Mutex::new(Incoming::default()));
+    let frame_size = cli.width as usize * cli.height as usize;
+    {
+        let incoming = incoming.clone();
+        thread::spawn(move || read_frames(frame_size, &incoming));
+    }
+
+    let mut animator = Animator::new();
+    animator.set_compression(CompressionCode::Lzma);
+    let handle = animator.handle();
+    {
+        let handle = handle.clone();
+        ctrlc::set_handler(move || handle.stop()).unwrap();
+    }
+
+    let stats = animator
+        .run(&connection, &mut PixelGrid::max_sized(), |pixels, _| {
+            let mut incoming = incoming.lock().unwrap();
+            let Some(frame) = incoming.frame.take() else {
+                if incoming.finished {
+                    handle.stop();
+                }
+                return;
+            };
+            drop(incoming);
+
+            let image = GrayImage::from_raw(cli.width, cli.height, frame)
+                .expect("frame has the wrong size");
+            *pixels = convert_image(&DynamicImage::ImageLuma8(image), &options);
+        })
+        .expect("send failed");
+
+    let incoming = incoming.lock().unwrap();
+    info!(
+        "received {} frames, skipped {}, sent {} frames",
+        incoming.received, incoming.skipped, stats.sent
+    );
+}
+
+/// Reads frames from stdin until the end, always keeping only the newest one.
+fn read_frames(frame_size: usize, incoming: &Mutex<Incoming>) {
+    let mut stdin = std::io::stdin().lock();
+    loop {
+        let mut frame = vec![0u8; frame_size];
+        if let Err(err) = stdin.read_exact(&mut frame) {
+            if err.kind() != ErrorKind::UnexpectedEof {
+                error!("could not read frame: {err}");
+            }
+            break;
+        }
+
+        let mut incoming = incoming.lock().unwrap();
+        if incoming.frame.is_some() {
+            incoming.skipped += 1;
+        }
+        incoming.frame = Some(frame);
+        incoming.received += 1;
+    }
+    incoming.lock().unwrap().finished = true;
+}
+
+impl From<ResizeArg> for Resize {
+    fn from(resize: ResizeArg) -> Self {
+        match resize {
+            ResizeArg::Fit => Resize::Fit,
+            ResizeArg::Fill => Resize::Fill,
+            ResizeArg::Stretch => Resize::Stretch,
+        }
+    }
+}
+
+impl From<DitheringArg> for Dithering {
+    fn from(dithering: DitheringArg) -> Self {
+        match dithering {
+            DitheringArg::None => Dithering::None,
+            DitheringArg::Ordered => Dithering::Ordered,
+            DitheringArg::FloydSteinberg => Dithering::FloydSteinberg,
+        }
+    }
+}
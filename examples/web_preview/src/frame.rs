@@ -0,0 +1,140 @@
+//! The messages sent to the viewer.
+//!
+//! A frame is the pixels as packed bytes, followed by the brightness and the text of every
+//! tile, one byte each. Messages start with a type byte:
+//!
+//! - `0`: a full frame
+//! - `1`: a diff, consisting of runs of a big-endian `u16` offset into the frame, a `u16`
+//!   length and that many bytes replacing the old ones
+
+use servicepoint2::{
+    DataRef, ScreenState, PIXEL_COUNT, TILE_HEIGHT, TILE_WIDTH,
+};
+
+/// the size of a frame in bytes
+pub const FRAME_SIZE: usize = PIXEL_COUNT / 8 + 2 * TILE_WIDTH * TILE_HEIGHT;
+
+const FULL: u8 = 0;
+const DIFF: u8 = 1;
+
+/// Unchanged bytes between two changes are included in the run if the gap is at most this
+/// long, as starting a new run costs four bytes.
+const MAX_GAP: usize = 4;
+
+/// Serializes the state into a frame.
+pub fn encode(state: &ScreenState) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(FRAME_SIZE);
+    frame.extend_from_slice(state.pixels().data_ref());
+    frame.extend_from_slice(state.brightness().data_ref());
+    frame.extend_from_slice(state.chars().data_ref());
+    frame
+}
+
+/// The message for showing a frame to a new viewer
+pub fn full_message(frame: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(frame.len() + 1);
+    message.push(FULL);
+    message.extend_from_slice(frame);
+    message
+}
+
+/// The message for changing `old` into `new`, whichever is smaller of a diff or a full frame.
+///
+/// returns: `None` if nothing changed
+pub fn update_message(old: &[u8], new: &[u8]) -> Option<Vec<u8>> {
+    let mut message = vec![DIFF];
+    let mut index = 0;
+    while index < new.len() {
+        if old[index] == new[index] {
+            index += 1;
+            continue;
+        }
+
+        // extend the run to later changes until the gap gets too big
+        let start = index;
+        let mut end = index + 1;
+        loop {
+            let limit = (end + MAX_GAP + 1)
+                .min(new.len())
+                .min(start + usize::from(u16::MAX));
+            match (end..limit).find(|i| old[*i] != new[*i]) {
+                Some(change) => end = change + 1,
+                None => break,
+            }
+        }
+
+        message.extend_from_slice(&(start as u16).to_be_bytes());
+        message.extend_from_slice(&((end - start) as u16).to_be_bytes());
+        message.extend_from_slice(&new[start..end]);
+        index = end;
+    }
+
+    match message.len() {
+        1 => None,
+        len if len > new.len() + 1 => Some(full_message(new)),
+        _ => Some(message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use servicepoint2::ScreenState;
+
+    use super::{encode, update_message, FRAME_SIZE};
+
+    /// Applies a message the same way the viewer does.
+    fn apply(frame: &mut [u8], message: &[u8]) {
+        if message[0] == 0 {
+            frame.copy_from_slice(&message[1..]);
+            return;
+        }
+        let mut rest = &message[1..];
+        while !rest.is_empty() {
+            let offset = usize::from(u16::from_be_bytes([rest[0], rest[1]]));
+            let len = usize::from(u16::from_be_bytes([rest[2], rest[3]]));
+            frame[offset..offset + len].copy_from_slice(&rest[4..4 + len]);
+            rest = &rest[4 + len..];
+        }
+    }
+
+    #[test]
+    fn frame_size() {
+        assert_eq!(encode(&ScreenState::new()).len(), FRAME_SIZE);
+    }
+
+    #[test]
+    fn unchanged() {
+        let frame = encode(&ScreenState::new());
+        assert_eq!(update_message(&frame, &frame), None);
+    }
+
+    #[test]
+    fn small_change_is_diff() {
+        let old = vec![0u8; 100];
+        let mut new = old.clone();
+        new[10] = 1;
+        new[13] = 2;
+        new[50] = 3;
+
+        let message = update_message(&old, &new).unwrap();
+        assert_eq!(message, [1, 0, 10, 0, 4, 1, 0, 0, 2, 0, 50, 0, 1, 3]);
+        let mut frame = old.clone();
+        apply(&mut frame, &message);
+        assert_eq!(frame, new);
+    }
+
+    #[test]
+    fn big_change_is_full() {
+        let old = encode(&ScreenState::new());
+        let mut new = old.clone();
+        for byte in new.iter_mut().step_by(3) {
+            *byte ^= 0xFF;
+        }
+
+        let message = update_message(&old, &new).unwrap();
+        assert_eq!(message[0], 0);
+        let mut frame = old.clone();
+        apply(&mut frame, &message);
+        assert_eq!(frame, new);
+    }
+}
@@ -0,0 +1,180 @@
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream, UdpSocket};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use clap::Parser;
+use log::{debug, info, warn};
+use tungstenite::{Message, WebSocket};
+
+use servicepoint2::{Command, Connection, Packet, ScreenState, FRAME_PACING};
+
+use crate::frame::{encode, full_message, update_message};
+
+mod frame;
+
+const VIEWER: &str = include_str!("viewer.html");
+
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Parser, Debug)]
+struct Cli {
+    /// address to receive packets on
+    #[arg(short, long, default_value = "0.0.0.0:2342")]
+    listen: String,
+    /// address to serve the viewer and the WebSocket on
+    #[arg(long, default_value = "127.0.0.1:8000")]
+    http: String,
+    /// also forward all packets to the real display
+    #[arg(short, long)]
+    forward: Option<String>,
+}
+
+/// The connected viewers and the frame they are showing
+struct Viewers {
+    sockets: Vec<WebSocket<TcpStream>>,
+    frame: Vec<u8>,
+}
+
+/// example: `cargo run --bin servicepoint-web -- --listen 0.0.0.0:2342`, then open
+/// http://127.0.0.1:8000 and send packets to port 2342
+fn main() {
+    env_logger::init();
+    let cli = Cli::parse();
+
+    let state = Arc::new(Mutex::new(ScreenState::new()));
+    let viewers = Arc::new(Mutex::new(Viewers {
+        sockets: vec![],
+        frame: encode(&ScreenState::new()),
+    }));
+
+    let listener = TcpListener::bind(&cli.http).expect("could not listen");
+    info!("viewer available at http://{}", cli.http);
+    thread::spawn({
+        let viewers = Arc::clone(&viewers);
+        move || serve(&listener, &viewers)
+    });
+    thread::spawn({
+        let state = Arc::clone(&state);
+        let viewers = Arc::clone(&viewers);
+        move || broadcast(&state, &viewers)
+    });
+
+    let forward = cli.forward.map(|destination| {
+        Connection::open(destination).expect("could not open connection")
+    });
+    let socket = UdpSocket::bind(&cli.listen).expect("could not bind socket");
+    let mut buffer = [0u8; u16::MAX as usize];
+    loop {
+        let (size, source) =
+            socket.recv_from(&mut buffer).expect("could not receive");
+        let data = &buffer[..size];
+        if let (Some(connection), Ok(packet)) =
+            (&forward, Packet::try_from(data))
+        {
+            if let Err(err) = connection.send(packet) {
+                warn!("could not forward to display: {err}");
+            }
+        }
+
+        let command = match Packet::try_from(data) {
+            Err(()) => {
+                warn!("ignoring {size} bytes from {source}");
+                continue;
+            }
+            Ok(packet) => match Command::try_from(packet) {
+                Err(err) => {
+                    warn!("ignoring invalid packet from {source}: {err:?}");
+                    continue;
+                }
+                Ok(command) => command,
+            },
+        };
+        debug!("{source} sent {command}");
+        state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .apply(&command);
+    }
+}
+
+/// Accepts connections and handles each of them in its own thread.
+fn serve(listener: &TcpListener, viewers: &Arc<Mutex<Viewers>>) {
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let viewers = Arc::clone(viewers);
+                thread::spawn(move || handle(stream, &viewers));
+            }
+            Err(err) => warn!("could not accept connection: {err}"),
+        }
+    }
+}
+
+/// Answers a request with the viewer, or upgrades it to a WebSocket.
+fn handle(mut stream: TcpStream, viewers: &Mutex<Viewers>) {
+    // a stuck viewer must not block the others for long
+    let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+    // clients that connect without sending a request are dropped
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    // only peek, the WebSocket handshake needs to read the request itself
+    let mut head = [0u8; 4096];
+    let size = stream.peek(&mut head).unwrap_or(0);
+    let head = String::from_utf8_lossy(&head[..size]).to_lowercase();
+    if !head.contains("upgrade: websocket") {
+        let mut request = [0u8; 4096];
+        let _ = stream.read(&mut request);
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{VIEWER}",
+            VIEWER.len()
+        );
+        if let Err(err) = stream.write_all(response.as_bytes()) {
+            warn!("could not send viewer: {err}");
+        }
+        return;
+    }
+
+    let mut socket = match tungstenite::accept(stream) {
+        Ok(socket) => socket,
+        Err(err) => {
+            warn!("WebSocket handshake failed: {err}");
+            return;
+        }
+    };
+    let mut viewers = viewers.lock().unwrap_or_else(PoisonError::into_inner);
+    match socket.send(Message::Binary(full_message(&viewers.frame))) {
+        Ok(()) => {
+            info!("viewer connected");
+            viewers.sockets.push(socket);
+        }
+        Err(err) => warn!("could not send first frame: {err}"),
+    }
+}
+
+/// Sends the changes of the state to all viewers, at most once per `FRAME_PACING`.
+fn broadcast(state: &Mutex<ScreenState>, viewers: &Mutex<Viewers>) {
+    loop {
+        thread::sleep(FRAME_PACING);
+        let frame =
+            encode(&state.lock().unwrap_or_else(PoisonError::into_inner));
+
+        let mut viewers =
+            viewers.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(message) = update_message(&viewers.frame, &frame) else {
+            continue;
+        };
+        viewers.frame = frame;
+        viewers.sockets.retain_mut(|socket| {
+            match socket.send(Message::Binary(message.clone())) {
+                Ok(()) => true,
+                Err(err) => {
+                    info!("viewer disconnected: {err}");
+                    false
+                }
+            }
+        });
+    }
+}
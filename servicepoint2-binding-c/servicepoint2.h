@@ -109,14 +109,88 @@ typedef struct sp2_CByteSlice {
 } sp2_CByteSlice;
 
 /**
- * Type alias for documenting the meaning of the u16 in enum values
+ * The header of the `BitmapLinear*` commands, which address a range of pixels.
  */
-typedef size_t sp2_Offset;
+typedef struct sp2_LinearHeader {
+    /**
+     * The raw command code
+     */
+    uint16_t command_code;
+    /**
+     * The offset of the first pixel in bytes
+     */
+    uint16_t offset;
+    /**
+     * The length of the uncompressed payload in bytes
+     */
+    uint16_t length;
+    /**
+     * The raw `CompressionCode` of the payload
+     */
+    uint16_t compression;
+    /**
+     * Unused, has to be 0
+     */
+    uint16_t reserved;
+} sp2_LinearHeader;
+
+/**
+ * The header of the `Cp437Data` and `CharBrightness` commands, which address a window in tiles.
+ */
+typedef struct sp2_TileHeader {
+    /**
+     * The raw command code
+     */
+    uint16_t command_code;
+    /**
+     * The left edge of the window in tiles
+     */
+    uint16_t tile_x;
+    /**
+     * The top edge of the window in tiles
+     */
+    uint16_t tile_y;
+    /**
+     * The width of the window in tiles
+     */
+    uint16_t tile_w;
+    /**
+     * The height of the window in tiles
+     */
+    uint16_t tile_h;
+} sp2_TileHeader;
+
+/**
+ * The header of a `BitmapLinearWin` command, which addresses a window in tile columns and
+ * pixel rows.
+ */
+typedef struct sp2_WindowHeader {
+    /**
+     * The raw command code
+     */
+    uint16_t command_code;
+    /**
+     * The left edge of the window in tiles
+     */
+    uint16_t tile_x;
+    /**
+     * The top edge of the window in pixels
+     */
+    uint16_t pixel_y;
+    /**
+     * The width of the window in tiles
+     */
+    uint16_t tile_w;
+    /**
+     * The height of the window in pixels
+     */
+    uint16_t pixel_h;
+} sp2_WindowHeader;
 
 /**
  * Type alias for documenting the meaning of the u16 in enum values
  */
-typedef uint8_t sp2_Brightness;
+typedef size_t sp2_Offset;
 
 #ifdef __cplusplus
 extern "C" {
@@ -296,9 +370,18 @@ struct sp2_Command *sp2_command_bitmap_linear_xor(sp2_Offset offset,
                                                   sp2_CompressionCode compression);
 
 /**
- * Allocates a new `Command::Brightness` instance
+ * Allocates a new `Command::Brightness` instance.
+ *
+ * Values above the maximum brightness are clamped.
+ */
+struct sp2_Command *sp2_command_brightness(uint8_t brightness);
+
+/**
+ * Allocates a new `Command::Brightness` instance.
+ *
+ * returns: NULL if the brightness is higher than the maximum of 11
  */
-struct sp2_Command *sp2_command_brightness(sp2_Brightness brightness);
+struct sp2_Command *sp2_command_brightness_checked(uint8_t brightness);
 
 /**
  * Allocates a new `Command::CharBrightness` instance.
@@ -370,6 +453,11 @@ struct sp2_Connection *sp2_connection_open(const char *host);
 bool sp2_connection_send(const struct sp2_Connection *connection,
                          struct sp2_Packet *command_ptr);
 
+/**
+ * Gets the raw command code of the `Packet`.
+ */
+uint16_t sp2_packet_command_code(const struct sp2_Packet *this_);
+
 /**
  * Deallocates a `Packet`.
  *
@@ -382,6 +470,17 @@ void sp2_packet_dealloc(struct sp2_Packet *this_);
  */
 struct sp2_Packet *sp2_packet_from_command(struct sp2_Command *command);
 
+/**
+ * Interprets the header of the `Packet` as the header of a `BitmapLinear*` command.
+ */
+struct sp2_LinearHeader sp2_packet_linear_header(const struct sp2_Packet *this_);
+
+/**
+ * Interprets the header of the `Packet` as the header of a `Cp437Data` or `CharBrightness`
+ * command.
+ */
+struct sp2_TileHeader sp2_packet_tile_header(const struct sp2_Packet *this_);
+
 /**
  * Tries to load a `Packet` from the passed array with the specified length.
  *
@@ -389,6 +488,11 @@ struct sp2_Packet *sp2_packet_from_command(struct sp2_Command *command);
  */
 struct sp2_Packet *sp2_packet_try_load(const uint8_t *data, size_t length);
 
+/**
+ * Interprets the header of the `Packet` as the header of a `BitmapLinearWin` command.
+ */
+struct sp2_WindowHeader sp2_packet_window_header(const struct sp2_Packet *this_);
+
 /**
  * Clones a `PixelGrid`.
  * The returned instance has to be freed with `pixel_grid_dealloc`.
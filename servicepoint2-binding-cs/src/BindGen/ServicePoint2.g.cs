@@ -119,10 +119,14 @@ namespace ServicePoint2.BindGen
         [DllImport(__DllName, EntryPoint = "sp2_command_fade_out", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         public static extern Command* sp2_command_fade_out();
 
-        /// <summary>Allocates a new `Command::Brightness` instance</summary>
+        /// <summary>Allocates a new `Command::Brightness` instance.  Values above the maximum brightness are clamped.</summary>
         [DllImport(__DllName, EntryPoint = "sp2_command_brightness", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         public static extern Command* sp2_command_brightness(byte brightness);
 
+        /// <summary>Allocates a new `Command::Brightness` instance.  returns: NULL if the brightness is higher than the maximum of 11</summary>
+        [DllImport(__DllName, EntryPoint = "sp2_command_brightness_checked", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        public static extern Command* sp2_command_brightness_checked(byte brightness);
+
         /// <summary>Allocates a new `Command::CharBrightness` instance. The passed `ByteGrid` gets deallocated in the process.</summary>
         [DllImport(__DllName, EntryPoint = "sp2_command_char_brightness", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         public static extern Command* sp2_command_char_brightness(nuint x, nuint y, ByteGrid* byte_grid);
@@ -217,6 +221,22 @@ namespace ServicePoint2.BindGen
         [DllImport(__DllName, EntryPoint = "sp2_packet_try_load", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         public static extern Packet* sp2_packet_try_load(byte* data, nuint length);
 
+        /// <summary>Gets the raw command code of the `Packet`.</summary>
+        [DllImport(__DllName, EntryPoint = "sp2_packet_command_code", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        public static extern ushort sp2_packet_command_code(Packet* @this);
+
+        /// <summary>Interprets the header of the `Packet` as the header of a `BitmapLinearWin` command.</summary>
+        [DllImport(__DllName, EntryPoint = "sp2_packet_window_header", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        public static extern WindowHeader sp2_packet_window_header(Packet* @this);
+
+        /// <summary>Interprets the header of the `Packet` as the header of a `Cp437Data` or `CharBrightness` command.</summary>
+        [DllImport(__DllName, EntryPoint = "sp2_packet_tile_header", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        public static extern TileHeader sp2_packet_tile_header(Packet* @this);
+
+        /// <summary>Interprets the header of the `Packet` as the header of a `BitmapLinear*` command.</summary>
+        [DllImport(__DllName, EntryPoint = "sp2_packet_linear_header", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        public static extern LinearHeader sp2_packet_linear_header(Packet* @this);
+
         /// <summary>Deallocates a `Packet`.  Note: do not call this if the instance has been consumed in another way, e.g. by sending it.</summary>
         [DllImport(__DllName, EntryPoint = "sp2_packet_dealloc", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         public static extern void sp2_packet_dealloc(Packet* @this);
@@ -256,6 +276,36 @@ namespace ServicePoint2.BindGen
     {
     }
 
+    [StructLayout(LayoutKind.Sequential)]
+    public unsafe partial struct WindowHeader
+    {
+        public ushort command_code;
+        public ushort tile_x;
+        public ushort pixel_y;
+        public ushort tile_w;
+        public ushort pixel_h;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    public unsafe partial struct TileHeader
+    {
+        public ushort command_code;
+        public ushort tile_x;
+        public ushort tile_y;
+        public ushort tile_w;
+        public ushort tile_h;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    public unsafe partial struct LinearHeader
+    {
+        public ushort command_code;
+        public ushort offset;
+        public ushort length;
+        public ushort compression;
+        public ushort reserved;
+    }
+
 
     public enum Command
     {
@@ -54,6 +54,15 @@ public sealed class Command : Sp2NativeInstance<BindGen.Command>
     }
 
     public static Command Brightness(byte brightness)
+    {
+        ArgumentOutOfRangeException.ThrowIfGreaterThan(brightness, Constants.MaxBrightness);
+        unsafe
+        {
+            return new Command(NativeMethods.sp2_command_brightness_checked(brightness));
+        }
+    }
+
+    public static Command BrightnessSaturating(byte brightness)
     {
         unsafe
         {
@@ -19,4 +19,10 @@ public static class Constants
 
     /// pixel count on whole screen
     public const int PixelCount = PixelWidth * PixelHeight;
+
+    /// the lowest brightness the display supports
+    public const byte MinBrightness = 0;
+
+    /// the highest brightness the display supports
+    public const byte MaxBrightness = 11;
 }
@@ -28,6 +28,50 @@ public sealed class Packet : Sp2NativeInstance<BindGen.Packet>
         }
     }
 
+    public ushort CommandCode
+    {
+        get
+        {
+            unsafe
+            {
+                return NativeMethods.sp2_packet_command_code(Instance);
+            }
+        }
+    }
+
+    public WindowHeader WindowHeader
+    {
+        get
+        {
+            unsafe
+            {
+                return NativeMethods.sp2_packet_window_header(Instance);
+            }
+        }
+    }
+
+    public TileHeader TileHeader
+    {
+        get
+        {
+            unsafe
+            {
+                return NativeMethods.sp2_packet_tile_header(Instance);
+            }
+        }
+    }
+
+    public LinearHeader LinearHeader
+    {
+        get
+        {
+            unsafe
+            {
+                return NativeMethods.sp2_packet_linear_header(Instance);
+            }
+        }
+    }
+
     private unsafe Packet(BindGen.Packet* instance) : base(instance)
     {
     }
@@ -0,0 +1,110 @@
+use std::ffi::c_int;
+
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::ffi;
+use pyo3::prelude::*;
+
+use servicepoint2::{BitVec, DataRef};
+
+use crate::buffer::{fill_buffer, release_buffer};
+
+/// A fixed-size vector of bits.
+///
+/// Supports the buffer protocol, the bytes are shared with the vector.
+#[pyclass(name = "BitVec")]
+#[derive(Debug, Clone)]
+pub struct PyBitVec(pub BitVec);
+
+#[pymethods]
+impl PyBitVec {
+    /// Creates a vector with all bits set to false.
+    ///
+    /// Raises `ValueError` if the size is not a multiple of 8.
+    #[new]
+    fn new(size: usize) -> PyResult<Self> {
+        if size / 8 * 8 != size {
+            return Err(PyValueError::new_err(format!(
+                "size {size} is not a multiple of 8"
+            )));
+        }
+        Ok(Self(BitVec::new(size)))
+    }
+
+    /// Creates a vector from a copy of the bytes, most significant bit first.
+    #[staticmethod]
+    fn load(data: &[u8]) -> Self {
+        Self(BitVec::from(data))
+    }
+
+    fn get(&self, index: usize) -> PyResult<bool> {
+        self.check_index(index)?;
+        Ok(self.0.get(index))
+    }
+
+    /// Sets the value of a bit and returns the old value.
+    fn set(&mut self, index: usize, value: bool) -> PyResult<bool> {
+        self.check_index(index)?;
+        Ok(self.0.set(index, value))
+    }
+
+    fn fill(&mut self, value: bool) {
+        self.0.fill(value);
+    }
+
+    fn clone(&self) -> Self {
+        Clone::clone(self)
+    }
+
+    fn __copy__(&self) -> Self {
+        Clone::clone(self)
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    fn __getitem__(&self, index: usize) -> PyResult<bool> {
+        self.get(index)
+    }
+
+    fn __setitem__(&mut self, index: usize, value: bool) -> PyResult<()> {
+        self.set(index, value).map(|_| ())
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+
+    fn __repr__(&self) -> String {
+        format!("BitVec({})", self.0.len())
+    }
+
+    /// A one-dimensional buffer of the packed bytes
+    unsafe fn __getbuffer__(
+        slf: Bound<'_, Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        let mut this = slf.borrow_mut();
+        let data = this.0.data_ref_mut();
+        let shape = [data.len()];
+        fill_buffer(view, flags, slf.as_any(), data, &shape)
+    }
+
+    unsafe fn __releasebuffer__(&self, view: *mut ffi::Py_buffer) {
+        release_buffer(view);
+    }
+}
+
+impl PyBitVec {
+    fn check_index(&self, index: usize) -> PyResult<()> {
+        if index < self.0.len() {
+            Ok(())
+        } else {
+            Err(PyIndexError::new_err(format!(
+                "cannot access bit {index}, the vector has {} bits",
+                self.0.len()
+            )))
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! The buffer protocol for the containers, giving Python direct access to the
+//! bytes of `DataRef::data_ref_mut`.
+
+use std::ffi::c_int;
+use std::ptr::{null_mut, slice_from_raw_parts_mut};
+
+use pyo3::exceptions::PyBufferError;
+use pyo3::{ffi, Bound, PyAny, PyResult};
+
+/// Exports `data` as a writable, C-contiguous buffer of unsigned bytes.
+///
+/// The shape and strides are allocated here and released again in
+/// `release_buffer`. Callers have to ensure `data` stays at the same place
+/// while `owner` is alive, which is the case as none of the containers can be
+/// resized.
+///
+/// # Safety
+///
+/// `view` has to be the pointer passed to `__getbuffer__`.
+pub unsafe fn fill_buffer(
+    view: *mut ffi::Py_buffer,
+    flags: c_int,
+    owner: &Bound<'_, PyAny>,
+    data: &mut [u8],
+    shape: &[usize],
+) -> PyResult<()> {
+    if view.is_null() {
+        return Err(PyBufferError::new_err("view is null"));
+    }
+    debug_assert_eq!(shape.iter().product::<usize>(), data.len());
+
+    (*view).obj = owner.clone().into_ptr();
+    (*view).buf = data.as_mut_ptr().cast();
+    (*view).len = data.len() as isize;
+    (*view).readonly = 0;
+    (*view).itemsize = 1;
+    (*view).format = if flags & ffi::PyBUF_FORMAT == ffi::PyBUF_FORMAT {
+        c"B".as_ptr().cast_mut()
+    } else {
+        null_mut()
+    };
+    set_dimensions(view, flags, shape);
+    (*view).suboffsets = null_mut();
+    Ok(())
+}
+
+/// Allocates the shape followed by the strides in bytes, which are only
+/// exposed if `flags` asks for them.
+///
+/// # Safety
+///
+/// `view` has to point to a valid `Py_buffer`.
+unsafe fn set_dimensions(
+    view: *mut ffi::Py_buffer,
+    flags: c_int,
+    shape: &[usize],
+) {
+    let mut dimensions: Vec<isize> =
+        shape.iter().map(|size| *size as isize).collect();
+    let mut stride = 1;
+    let mut strides: Vec<isize> = shape
+        .iter()
+        .rev()
+        .map(|size| {
+            let current = stride;
+            stride *= *size as isize;
+            current
+        })
+        .collect();
+    strides.reverse();
+    dimensions.extend(strides);
+    let ndim = shape.len();
+    let dimensions =
+        Box::into_raw(dimensions.into_boxed_slice()).cast::<isize>();
+
+    (*view).ndim = ndim as c_int;
+    (*view).shape = if flags & ffi::PyBUF_ND == ffi::PyBUF_ND {
+        dimensions
+    } else {
+        null_mut()
+    };
+    (*view).strides = if flags & ffi::PyBUF_STRIDES == ffi::PyBUF_STRIDES {
+        dimensions.add(ndim)
+    } else {
+        null_mut()
+    };
+    (*view).internal = dimensions.cast();
+}
+
+/// Frees the shape and strides allocated by `fill_buffer`.
+///
+/// # Safety
+///
+/// `view` has to be the pointer passed to `__releasebuffer__`.
+pub unsafe fn release_buffer(view: *mut ffi::Py_buffer) {
+    let len = 2 * (*view).ndim as usize;
+    drop(Box::from_raw(slice_from_raw_parts_mut(
+        (*view).internal.cast::<isize>(),
+        len,
+    )));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr::null_mut;
+    use std::slice::from_raw_parts;
+
+    use pyo3::ffi;
+
+    use super::{release_buffer, set_dimensions};
+
+    #[test]
+    fn shape_and_strides() {
+        let mut view = ffi::Py_buffer::new();
+        unsafe {
+            set_dimensions(&mut view, ffi::PyBUF_FULL, &[20, 56]);
+            assert_eq!(view.ndim, 2);
+            assert_eq!(from_raw_parts(view.shape, 2), [20, 56]);
+            assert_eq!(from_raw_parts(view.strides, 2), [56, 1]);
+            release_buffer(&mut view);
+
+            set_dimensions(&mut view, ffi::PyBUF_FULL, &[2, 3, 4]);
+            assert_eq!(from_raw_parts(view.shape, 3), [2, 3, 4]);
+            assert_eq!(from_raw_parts(view.strides, 3), [12, 4, 1]);
+            release_buffer(&mut view);
+        }
+    }
+
+    #[test]
+    fn only_requested_dimensions() {
+        let mut view = ffi::Py_buffer::new();
+        unsafe {
+            set_dimensions(&mut view, ffi::PyBUF_ND, &[1760]);
+            assert_eq!(from_raw_parts(view.shape, 1), [1760]);
+            assert_eq!(view.strides, null_mut());
+            release_buffer(&mut view);
+
+            set_dimensions(&mut view, ffi::PyBUF_SIMPLE, &[1760]);
+            assert_eq!(view.shape, null_mut());
+            assert_eq!(view.strides, null_mut());
+            assert!(!view.internal.is_null());
+            release_buffer(&mut view);
+        }
+    }
+}
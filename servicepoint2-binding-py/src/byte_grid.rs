@@ -0,0 +1,114 @@
+use std::ffi::c_int;
+
+use pyo3::ffi;
+use pyo3::prelude::*;
+
+use servicepoint2::{ByteGrid, DataRef, Grid};
+
+use crate::buffer::{fill_buffer, release_buffer};
+use crate::grid::{check_position, check_size, check_window};
+
+/// A 2D grid of bytes, used for text and the brightness of tiles.
+///
+/// Supports the buffer protocol with the shape `(height, width)`, so
+/// `numpy.asarray(grid)[:] = array` changes the grid in place.
+#[pyclass(name = "ByteGrid")]
+#[derive(Debug, Clone)]
+pub struct PyByteGrid(pub ByteGrid);
+
+#[pymethods]
+impl PyByteGrid {
+    /// Creates a grid with all bytes set to 0.
+    #[new]
+    fn new(width: usize, height: usize) -> Self {
+        Self(ByteGrid::new(width, height))
+    }
+
+    /// Creates a grid from a copy of the bytes, row by row.
+    ///
+    /// Raises `ValueError` if the size does not match.
+    #[staticmethod]
+    fn load(width: usize, height: usize, data: &[u8]) -> PyResult<Self> {
+        check_size(data.len(), width * height)?;
+        Ok(Self(ByteGrid::load(width, height, data)))
+    }
+
+    fn get(&self, x: usize, y: usize) -> PyResult<u8> {
+        check_position(self.0.width(), self.0.height(), x, y)?;
+        Ok(self.0.get(x, y))
+    }
+
+    /// Sets the byte at the position and returns the old value.
+    fn set(&mut self, x: usize, y: usize, value: u8) -> PyResult<u8> {
+        check_position(self.0.width(), self.0.height(), x, y)?;
+        Ok(self.0.set(x, y, value))
+    }
+
+    fn fill(&mut self, value: u8) {
+        self.0.fill(value);
+    }
+
+    /// A copy of the window starting at `x`, `y` with the size `w`, `h`
+    fn window(&self, x: usize, y: usize, w: usize, h: usize) -> PyResult<Self> {
+        check_window(self.0.width(), self.0.height(), (x, y, w, h))?;
+        Ok(Self(self.0.window(x, y, w, h)))
+    }
+
+    #[getter]
+    fn width(&self) -> usize {
+        self.0.width()
+    }
+
+    #[getter]
+    fn height(&self) -> usize {
+        self.0.height()
+    }
+
+    fn clone(&self) -> Self {
+        Clone::clone(self)
+    }
+
+    fn __copy__(&self) -> Self {
+        Clone::clone(self)
+    }
+
+    fn __getitem__(&self, position: (usize, usize)) -> PyResult<u8> {
+        let (x, y) = position;
+        self.get(x, y)
+    }
+
+    fn __setitem__(
+        &mut self,
+        position: (usize, usize),
+        value: u8,
+    ) -> PyResult<()> {
+        let (x, y) = position;
+        self.set(x, y, value).map(|_| ())
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ByteGrid({}, {})", self.0.width(), self.0.height())
+    }
+
+    unsafe fn __getbuffer__(
+        slf: Bound<'_, Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        let mut this = slf.borrow_mut();
+        let shape = [this.0.height(), this.0.width()];
+        fill_buffer(view, flags, slf.as_any(), this.0.data_ref_mut(), &shape)
+    }
+
+    unsafe fn __releasebuffer__(&self, view: *mut ffi::Py_buffer) {
+        release_buffer(view);
+    }
+}
@@ -0,0 +1,187 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use servicepoint2::{
+    Brightness, BrightnessOutOfRange, Command, Offset, Origin, Packet,
+    TILE_SIZE,
+};
+
+use crate::bit_vec::PyBitVec;
+use crate::byte_grid::PyByteGrid;
+use crate::compression_code::PyCompressionCode;
+use crate::pixel_grid::PyPixelGrid;
+
+/// A command to send to the display.
+///
+/// Grids and vectors passed to the constructors are copied, so they can
+/// still be changed afterwards.
+#[pyclass(name = "Command", frozen)]
+#[derive(Debug, Clone)]
+pub struct PyCommand(pub Command);
+
+#[pymethods]
+impl PyCommand {
+    /// Set all pixels to the off state
+    #[staticmethod]
+    fn clear() -> Self {
+        Self(Command::Clear)
+    }
+
+    /// Kills the udp daemon, usually results in a reboot of the display.
+    #[staticmethod]
+    fn hard_reset() -> Self {
+        Self(Command::HardReset)
+    }
+
+    #[staticmethod]
+    fn fade_out() -> Self {
+        Self(Command::FadeOut)
+    }
+
+    /// Set the brightness of all tiles.
+    ///
+    /// Raises `ValueError` if the brightness is higher than `MAX_BRIGHTNESS`.
+    #[staticmethod]
+    fn brightness(brightness: u8) -> PyResult<Self> {
+        let brightness = Brightness::new(brightness).ok_or_else(|| {
+            PyValueError::new_err(BrightnessOutOfRange(brightness).to_string())
+        })?;
+        Ok(Self(Command::Brightness(brightness)))
+    }
+
+    /// Like `brightness`, using `MAX_BRIGHTNESS` for values that are too big
+    #[staticmethod]
+    fn brightness_saturating(brightness: u8) -> Self {
+        Self(Command::Brightness(Brightness::saturating_from(brightness)))
+    }
+
+    /// Set the brightness of tiles, starting at the tile `x`, `y`
+    #[staticmethod]
+    fn char_brightness(x: usize, y: usize, grid: &PyByteGrid) -> Self {
+        Self(Command::CharBrightness(Origin(x, y), grid.0.clone()))
+    }
+
+    /// Legacy command code, gets ignored by the real display.
+    #[staticmethod]
+    #[allow(deprecated)]
+    fn bitmap_legacy() -> Self {
+        Self(Command::BitmapLegacy)
+    }
+
+    /// Set pixel data starting at the pixel offset.
+    #[staticmethod]
+    fn bitmap_linear(
+        offset: Offset,
+        bits: &PyBitVec,
+        compression: PyCompressionCode,
+    ) -> Self {
+        Self(Command::BitmapLinear(
+            offset,
+            bits.0.clone(),
+            compression.into(),
+        ))
+    }
+
+    /// Set pixel data according to an and-mask starting at the offset.
+    #[staticmethod]
+    fn bitmap_linear_and(
+        offset: Offset,
+        bits: &PyBitVec,
+        compression: PyCompressionCode,
+    ) -> Self {
+        Self(Command::BitmapLinearAnd(
+            offset,
+            bits.0.clone(),
+            compression.into(),
+        ))
+    }
+
+    /// Set pixel data according to an or-mask starting at the offset.
+    #[staticmethod]
+    fn bitmap_linear_or(
+        offset: Offset,
+        bits: &PyBitVec,
+        compression: PyCompressionCode,
+    ) -> Self {
+        Self(Command::BitmapLinearOr(
+            offset,
+            bits.0.clone(),
+            compression.into(),
+        ))
+    }
+
+    /// Set pixel data according to a xor-mask starting at the offset.
+    #[staticmethod]
+    fn bitmap_linear_xor(
+        offset: Offset,
+        bits: &PyBitVec,
+        compression: PyCompressionCode,
+    ) -> Self {
+        Self(Command::BitmapLinearXor(
+            offset,
+            bits.0.clone(),
+            compression.into(),
+        ))
+    }
+
+    /// Show CP437 encoded text, starting at the tile `x`, `y`
+    #[staticmethod]
+    fn cp437_data(x: usize, y: usize, grid: &PyByteGrid) -> Self {
+        Self(Command::Cp437Data(Origin(x, y), grid.0.clone()))
+    }
+
+    /// Sets a window of pixels starting at the pixel `x`, `y`.
+    ///
+    /// Raises `ValueError` if `x` is not a multiple of `TILE_SIZE`.
+    #[staticmethod]
+    fn bitmap_linear_win(
+        x: usize,
+        y: usize,
+        grid: &PyPixelGrid,
+        compression: PyCompressionCode,
+    ) -> PyResult<Self> {
+        if x / TILE_SIZE * TILE_SIZE != x {
+            return Err(PyValueError::new_err(format!(
+                "x {x} is not a multiple of {TILE_SIZE}"
+            )));
+        }
+        Ok(Self(Command::BitmapLinearWin(
+            Origin(x, y),
+            grid.0.clone(),
+            compression.into(),
+        )))
+    }
+
+    /// Reads a command from the bytes of a packet.
+    ///
+    /// Raises `ValueError` if the bytes are not a valid command.
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        let packet = Packet::try_from(data).map_err(|()| {
+            PyValueError::new_err("too short for a packet header")
+        })?;
+        let command = Command::try_from(packet).map_err(|err| {
+            PyValueError::new_err(format!("invalid command: {err:?}"))
+        })?;
+        Ok(Self(command))
+    }
+
+    /// The bytes of the packet that is sent for this command
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        let data: Vec<u8> = Packet::from(self.0.clone()).into();
+        PyBytes::new(py, &data)
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Command({})", self.0)
+    }
+}
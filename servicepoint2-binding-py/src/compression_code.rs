@@ -0,0 +1,25 @@
+use pyo3::prelude::*;
+
+/// Specifies the kind of compression to use.
+#[pyclass(name = "CompressionCode", eq, eq_int, frozen)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PyCompressionCode {
+    Uncompressed,
+    Zlib,
+    Bzip2,
+    Lzma,
+    Zstd,
+}
+
+impl From<PyCompressionCode> for servicepoint2::CompressionCode {
+    fn from(value: PyCompressionCode) -> Self {
+        use servicepoint2::CompressionCode;
+        match value {
+            PyCompressionCode::Uncompressed => CompressionCode::Uncompressed,
+            PyCompressionCode::Zlib => CompressionCode::Zlib,
+            PyCompressionCode::Bzip2 => CompressionCode::Bzip2,
+            PyCompressionCode::Lzma => CompressionCode::Lzma,
+            PyCompressionCode::Zstd => CompressionCode::Zstd,
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use pyo3::prelude::*;
+
+use servicepoint2::Connection;
+
+use crate::command::PyCommand;
+
+/// A connection to the display over UDP.
+#[pyclass(name = "Connection", frozen)]
+pub struct PyConnection(Connection);
+
+#[pymethods]
+impl PyConnection {
+    /// Opens a connection to the display at an address like
+    /// `"localhost:2342"`.
+    ///
+    /// Raises `OSError` if the address cannot be resolved.
+    #[staticmethod]
+    fn open(address: &str) -> PyResult<Self> {
+        Ok(Self(Connection::open(address)?))
+    }
+
+    /// Sends a copy of the command, so it can be sent again later.
+    fn send(&self, command: &PyCommand) -> PyResult<()> {
+        self.0.send(command.0.clone().into())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+//! Bounds checks for the grids, raising Python exceptions instead of panicking.
+
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::PyResult;
+
+pub fn check_position(
+    width: usize,
+    height: usize,
+    x: usize,
+    y: usize,
+) -> PyResult<()> {
+    if x < width && y < height {
+        Ok(())
+    } else {
+        Err(PyIndexError::new_err(format!(
+            "cannot access {x}-{y}, the grid is {width}x{height}"
+        )))
+    }
+}
+
+pub fn check_window(
+    width: usize,
+    height: usize,
+    (x, y, w, h): (usize, usize, usize, usize),
+) -> PyResult<()> {
+    if x + w <= width && y + h <= height {
+        Ok(())
+    } else {
+        Err(PyIndexError::new_err(format!(
+            "window {w}x{h} at {x}-{y} does not fit into {width}x{height}"
+        )))
+    }
+}
+
+pub fn check_size(len: usize, expected: usize) -> PyResult<()> {
+    if len == expected {
+        Ok(())
+    } else {
+        Err(PyValueError::new_err(format!(
+            "expected {expected} bytes, got {len}"
+        )))
+    }
+}
@@ -0,0 +1,47 @@
+//! Python bindings for the `servicepoint2` crate, built with `maturin`.
+
+use pyo3::prelude::*;
+
+use servicepoint2::{
+    Brightness, FRAME_PACING, PIXEL_COUNT, PIXEL_HEIGHT, PIXEL_WIDTH,
+    TILE_HEIGHT, TILE_SIZE, TILE_WIDTH,
+};
+
+use crate::bit_vec::PyBitVec;
+use crate::byte_grid::PyByteGrid;
+use crate::command::PyCommand;
+use crate::compression_code::PyCompressionCode;
+use crate::connection::PyConnection;
+use crate::pixel_grid::PyPixelGrid;
+
+mod bit_vec;
+mod buffer;
+mod byte_grid;
+mod command;
+mod compression_code;
+mod connection;
+mod grid;
+mod pixel_grid;
+
+#[pymodule]
+#[pyo3(name = "servicepoint2")]
+fn py_module(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyConnection>()?;
+    module.add_class::<PyCommand>()?;
+    module.add_class::<PyPixelGrid>()?;
+    module.add_class::<PyByteGrid>()?;
+    module.add_class::<PyBitVec>()?;
+    module.add_class::<PyCompressionCode>()?;
+
+    module.add("TILE_SIZE", TILE_SIZE)?;
+    module.add("TILE_WIDTH", TILE_WIDTH)?;
+    module.add("TILE_HEIGHT", TILE_HEIGHT)?;
+    module.add("PIXEL_WIDTH", PIXEL_WIDTH)?;
+    module.add("PIXEL_HEIGHT", PIXEL_HEIGHT)?;
+    module.add("PIXEL_COUNT", PIXEL_COUNT)?;
+    // in seconds, to be used with `time.sleep`
+    module.add("FRAME_PACING", FRAME_PACING.as_secs_f64())?;
+    module.add("MIN_BRIGHTNESS", u8::from(Brightness::MIN))?;
+    module.add("MAX_BRIGHTNESS", u8::from(Brightness::MAX))?;
+    Ok(())
+}
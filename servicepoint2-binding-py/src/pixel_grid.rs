@@ -0,0 +1,138 @@
+use std::ffi::c_int;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::ffi;
+use pyo3::prelude::*;
+
+use servicepoint2::{DataRef, Grid, PixelGrid};
+
+use crate::buffer::{fill_buffer, release_buffer};
+use crate::grid::{check_position, check_size, check_window};
+
+/// A 2D grid of pixels, packed into bytes with the most significant bit first.
+///
+/// Supports the buffer protocol with the shape `(height, width / 8)`, so
+/// `numpy.asarray(grid)[:] = numpy.packbits(array, axis=1)` changes the grid
+/// in place.
+#[pyclass(name = "PixelGrid")]
+#[derive(Debug, Clone)]
+pub struct PyPixelGrid(pub PixelGrid);
+
+#[pymethods]
+impl PyPixelGrid {
+    /// Creates a grid with all pixels off.
+    ///
+    /// Raises `ValueError` if the width is not a multiple of 8.
+    #[new]
+    fn new(width: usize, height: usize) -> PyResult<Self> {
+        check_width(width)?;
+        Ok(Self(PixelGrid::new(width, height)))
+    }
+
+    /// Creates a grid with the size of the whole screen.
+    #[staticmethod]
+    fn max_sized() -> Self {
+        Self(PixelGrid::max_sized())
+    }
+
+    /// Creates a grid from a copy of the packed bytes, row by row.
+    ///
+    /// Raises `ValueError` if the width is not a multiple of 8 or the size
+    /// does not match.
+    #[staticmethod]
+    fn load(width: usize, height: usize, data: &[u8]) -> PyResult<Self> {
+        check_width(width)?;
+        check_size(data.len(), width / 8 * height)?;
+        Ok(Self(PixelGrid::load(width, height, data)))
+    }
+
+    fn get(&self, x: usize, y: usize) -> PyResult<bool> {
+        check_position(self.0.width(), self.0.height(), x, y)?;
+        Ok(self.0.get(x, y))
+    }
+
+    /// Sets the pixel at the position and returns the old value.
+    fn set(&mut self, x: usize, y: usize, value: bool) -> PyResult<bool> {
+        check_position(self.0.width(), self.0.height(), x, y)?;
+        Ok(self.0.set(x, y, value))
+    }
+
+    fn fill(&mut self, value: bool) {
+        self.0.fill(value);
+    }
+
+    /// A copy of the window starting at `x`, `y` with the size `w`, `h`
+    fn window(&self, x: usize, y: usize, w: usize, h: usize) -> PyResult<Self> {
+        check_width(w)?;
+        check_window(self.0.width(), self.0.height(), (x, y, w, h))?;
+        Ok(Self(self.0.window(x, y, w, h)))
+    }
+
+    #[getter]
+    fn width(&self) -> usize {
+        self.0.width()
+    }
+
+    #[getter]
+    fn height(&self) -> usize {
+        self.0.height()
+    }
+
+    fn clone(&self) -> Self {
+        Clone::clone(self)
+    }
+
+    fn __copy__(&self) -> Self {
+        Clone::clone(self)
+    }
+
+    fn __getitem__(&self, position: (usize, usize)) -> PyResult<bool> {
+        let (x, y) = position;
+        self.get(x, y)
+    }
+
+    fn __setitem__(
+        &mut self,
+        position: (usize, usize),
+        value: bool,
+    ) -> PyResult<()> {
+        let (x, y) = position;
+        self.set(x, y, value).map(|_| ())
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("PixelGrid({}, {})", self.0.width(), self.0.height())
+    }
+
+    unsafe fn __getbuffer__(
+        slf: Bound<'_, Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        let mut this = slf.borrow_mut();
+        let shape = [this.0.height(), this.0.width() / 8];
+        fill_buffer(view, flags, slf.as_any(), this.0.data_ref_mut(), &shape)
+    }
+
+    unsafe fn __releasebuffer__(&self, view: *mut ffi::Py_buffer) {
+        release_buffer(view);
+    }
+}
+
+fn check_width(width: usize) -> PyResult<()> {
+    if width / 8 * 8 == width {
+        Ok(())
+    } else {
+        Err(PyValueError::new_err(format!(
+            "width {width} is not a multiple of 8"
+        )))
+    }
+}
@@ -0,0 +1,70 @@
+use wasm_bindgen::prelude::*;
+
+use servicepoint2::{BitVec, DataRef};
+
+use crate::grid::check_multiple_of_8;
+
+/// A fixed-size vector of bits.
+#[wasm_bindgen(js_name = BitVec)]
+#[derive(Debug, Clone)]
+pub struct JsBitVec(pub(crate) BitVec);
+
+#[wasm_bindgen(js_class = BitVec)]
+impl JsBitVec {
+    /// Creates a vector with all bits set to false.
+    ///
+    /// Throws if the size is not a multiple of 8.
+    #[wasm_bindgen(constructor)]
+    pub fn new(size: usize) -> Result<JsBitVec, JsError> {
+        check_multiple_of_8("size", size)?;
+        Ok(Self(BitVec::new(size)))
+    }
+
+    /// Creates a vector from a copy of the bytes, most significant bit first.
+    pub fn load(data: &[u8]) -> JsBitVec {
+        Self(BitVec::from(data))
+    }
+
+    pub fn get(&self, index: usize) -> Result<bool, JsError> {
+        self.check_index(index)?;
+        Ok(self.0.get(index))
+    }
+
+    /// Sets the value of a bit and returns the old value.
+    pub fn set(&mut self, index: usize, value: bool) -> Result<bool, JsError> {
+        self.check_index(index)?;
+        Ok(self.0.set(index, value))
+    }
+
+    pub fn fill(&mut self, value: bool) {
+        self.0.fill(value);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.0.len()
+    }
+
+    /// A copy of the packed bytes
+    pub fn data(&self) -> Vec<u8> {
+        self.0.data_ref().to_vec()
+    }
+
+    #[wasm_bindgen(js_name = clone)]
+    pub fn js_clone(&self) -> JsBitVec {
+        self.clone()
+    }
+}
+
+impl JsBitVec {
+    fn check_index(&self, index: usize) -> Result<(), JsError> {
+        if index < self.0.len() {
+            Ok(())
+        } else {
+            Err(JsError::new(&format!(
+                "cannot access bit {index}, the vector has {} bits",
+                self.0.len()
+            )))
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use wasm_bindgen::prelude::*;
+
+use servicepoint2::{ByteGrid, DataRef, Grid};
+
+use crate::grid::{check_position, check_size};
+
+/// A 2D grid of bytes, used for text and the brightness of tiles.
+#[wasm_bindgen(js_name = ByteGrid)]
+#[derive(Debug, Clone)]
+pub struct JsByteGrid(pub(crate) ByteGrid);
+
+#[wasm_bindgen(js_class = ByteGrid)]
+impl JsByteGrid {
+    /// Creates a grid with all bytes set to 0.
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: usize, height: usize) -> JsByteGrid {
+        Self(ByteGrid::new(width, height))
+    }
+
+    /// Creates a grid from a copy of the bytes, row by row.
+    ///
+    /// Throws if the size does not match.
+    pub fn load(
+        width: usize,
+        height: usize,
+        data: &[u8],
+    ) -> Result<JsByteGrid, JsError> {
+        check_size(data.len(), width * height)?;
+        Ok(Self(ByteGrid::load(width, height, data)))
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Result<u8, JsError> {
+        check_position(self.0.width(), self.0.height(), x, y)?;
+        Ok(self.0.get(x, y))
+    }
+
+    /// Sets the byte at the position and returns the old value.
+    pub fn set(
+        &mut self,
+        x: usize,
+        y: usize,
+        value: u8,
+    ) -> Result<u8, JsError> {
+        check_position(self.0.width(), self.0.height(), x, y)?;
+        Ok(self.0.set(x, y, value))
+    }
+
+    pub fn fill(&mut self, value: u8) {
+        self.0.fill(value);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> usize {
+        self.0.width()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> usize {
+        self.0.height()
+    }
+
+    /// A copy of the bytes, row by row
+    pub fn data(&self) -> Vec<u8> {
+        self.0.data_ref().to_vec()
+    }
+
+    #[wasm_bindgen(js_name = clone)]
+    pub fn js_clone(&self) -> JsByteGrid {
+        self.clone()
+    }
+}
@@ -0,0 +1,271 @@
+use wasm_bindgen::prelude::*;
+
+use servicepoint2::{
+    Brightness, BrightnessOutOfRange, Command, Offset, Origin, Packet,
+    TILE_SIZE,
+};
+
+use crate::bit_vec::JsBitVec;
+use crate::byte_grid::JsByteGrid;
+use crate::compression_code::CompressionCode;
+use crate::pixel_grid::JsPixelGrid;
+
+/// A command to send to the display.
+///
+/// Grids and vectors passed to the constructors are copied, so they can
+/// still be changed afterwards.
+#[wasm_bindgen(js_name = Command)]
+#[derive(Debug, Clone)]
+pub struct JsCommand(pub(crate) Command);
+
+#[wasm_bindgen(js_class = Command)]
+impl JsCommand {
+    /// Set all pixels to the off state
+    pub fn clear() -> JsCommand {
+        Self(Command::Clear)
+    }
+
+    /// Kills the udp daemon, usually results in a reboot of the display.
+    #[wasm_bindgen(js_name = hardReset)]
+    pub fn hard_reset() -> JsCommand {
+        Self(Command::HardReset)
+    }
+
+    #[wasm_bindgen(js_name = fadeOut)]
+    pub fn fade_out() -> JsCommand {
+        Self(Command::FadeOut)
+    }
+
+    /// Set the brightness of all tiles.
+    ///
+    /// Throws if the brightness is higher than `Constants.MAX_BRIGHTNESS`.
+    pub fn brightness(brightness: u8) -> Result<JsCommand, JsError> {
+        let brightness = Brightness::new(brightness)
+            .ok_or(BrightnessOutOfRange(brightness))?;
+        Ok(Self(Command::Brightness(brightness)))
+    }
+
+    /// Like `brightness`, using `Constants.MAX_BRIGHTNESS` for values that are
+    /// too big
+    #[wasm_bindgen(js_name = brightnessSaturating)]
+    pub fn brightness_saturating(brightness: u8) -> JsCommand {
+        Self(Command::Brightness(Brightness::saturating_from(brightness)))
+    }
+
+    /// Set the brightness of tiles, starting at the tile `x`, `y`
+    #[wasm_bindgen(js_name = charBrightness)]
+    pub fn char_brightness(x: usize, y: usize, grid: &JsByteGrid) -> JsCommand {
+        Self(Command::CharBrightness(Origin(x, y), grid.0.clone()))
+    }
+
+    /// Set pixel data starting at the pixel offset.
+    #[wasm_bindgen(js_name = bitmapLinear)]
+    pub fn bitmap_linear(
+        offset: Offset,
+        bits: &JsBitVec,
+        compression: CompressionCode,
+    ) -> JsCommand {
+        Self(Command::BitmapLinear(
+            offset,
+            bits.0.clone(),
+            compression.into(),
+        ))
+    }
+
+    /// Set pixel data according to an and-mask starting at the offset.
+    #[wasm_bindgen(js_name = bitmapLinearAnd)]
+    pub fn bitmap_linear_and(
+        offset: Offset,
+        bits: &JsBitVec,
+        compression: CompressionCode,
+    ) -> JsCommand {
+        Self(Command::BitmapLinearAnd(
+            offset,
+            bits.0.clone(),
+            compression.into(),
+        ))
+    }
+
+    /// Set pixel data according to an or-mask starting at the offset.
+    #[wasm_bindgen(js_name = bitmapLinearOr)]
+    pub fn bitmap_linear_or(
+        offset: Offset,
+        bits: &JsBitVec,
+        compression: CompressionCode,
+    ) -> JsCommand {
+        Self(Command::BitmapLinearOr(
+            offset,
+            bits.0.clone(),
+            compression.into(),
+        ))
+    }
+
+    /// Set pixel data according to a xor-mask starting at the offset.
+    #[wasm_bindgen(js_name = bitmapLinearXor)]
+    pub fn bitmap_linear_xor(
+        offset: Offset,
+        bits: &JsBitVec,
+        compression: CompressionCode,
+    ) -> JsCommand {
+        Self(Command::BitmapLinearXor(
+            offset,
+            bits.0.clone(),
+            compression.into(),
+        ))
+    }
+
+    /// Show CP437 encoded text, starting at the tile `x`, `y`
+    #[wasm_bindgen(js_name = cp437Data)]
+    pub fn cp437_data(x: usize, y: usize, grid: &JsByteGrid) -> JsCommand {
+        Self(Command::Cp437Data(Origin(x, y), grid.0.clone()))
+    }
+
+    /// Sets a window of pixels starting at the pixel `x`, `y`.
+    ///
+    /// Throws if `x` is not a multiple of `Constants.TILE_SIZE`.
+    #[wasm_bindgen(js_name = bitmapLinearWin)]
+    pub fn bitmap_linear_win(
+        x: usize,
+        y: usize,
+        grid: &JsPixelGrid,
+        compression: CompressionCode,
+    ) -> Result<JsCommand, JsError> {
+        check_tile_aligned(x).map_err(|err| JsError::new(&err))?;
+        Ok(Self(Command::BitmapLinearWin(
+            Origin(x, y),
+            grid.0.clone(),
+            compression.into(),
+        )))
+    }
+
+    /// Reads a command from the bytes of a packet.
+    ///
+    /// Throws if the bytes are not a valid command.
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(data: &[u8]) -> Result<JsCommand, JsError> {
+        let command =
+            command_from_bytes(data).map_err(|err| JsError::new(&err))?;
+        Ok(Self(command))
+    }
+
+    /// The bytes of the packet that is sent for this command
+    #[wasm_bindgen(js_name = toBytes)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        Packet::from(self.0.clone()).into()
+    }
+
+    #[wasm_bindgen(js_name = toString)]
+    pub fn js_to_string(&self) -> String {
+        self.0.to_string()
+    }
+
+    #[wasm_bindgen(js_name = clone)]
+    pub fn js_clone(&self) -> JsCommand {
+        self.clone()
+    }
+}
+
+// The checks return plain strings, as `JsError` can only be created when
+// running as WebAssembly.
+
+fn check_tile_aligned(x: usize) -> Result<(), String> {
+    if x / TILE_SIZE * TILE_SIZE == x {
+        Ok(())
+    } else {
+        Err(format!("x {x} is not a multiple of {TILE_SIZE}"))
+    }
+}
+
+fn command_from_bytes(data: &[u8]) -> Result<Command, String> {
+    let packet = Packet::try_from(data)
+        .map_err(|()| String::from("too short for a packet header"))?;
+    Command::try_from(packet).map_err(|err| format!("invalid command: {err:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use servicepoint2::{
+        BitVec, ByteGrid, Command, Grid, Origin, PixelGrid, TILE_SIZE,
+    };
+
+    use crate::bit_vec::JsBitVec;
+    use crate::byte_grid::JsByteGrid;
+    use crate::compression_code::CompressionCode;
+    use crate::pixel_grid::JsPixelGrid;
+
+    use super::{check_tile_aligned, command_from_bytes, JsCommand};
+
+    fn round_trip(command: &JsCommand) -> Command {
+        JsCommand::from_bytes(&command.to_bytes()).unwrap().0
+    }
+
+    #[test]
+    fn round_trips() {
+        let mut pixels = PixelGrid::new(16, 2);
+        pixels.set(9, 1, true);
+        let mut brightnesses = ByteGrid::new(2, 3);
+        brightnesses.set(1, 2, 7);
+        let mut text = ByteGrid::new(2, 3);
+        text.set(1, 2, b'a');
+        let mut bits = BitVec::new(16);
+        bits.set(3, true);
+
+        let commands = [
+            JsCommand::clear(),
+            JsCommand::hard_reset(),
+            JsCommand::fade_out(),
+            JsCommand::brightness_saturating(5),
+            JsCommand::char_brightness(1, 2, &JsByteGrid(brightnesses)),
+            JsCommand::cp437_data(3, 4, &JsByteGrid(text)),
+            JsCommand::bitmap_linear(
+                8,
+                &JsBitVec(bits.clone()),
+                CompressionCode::Zlib,
+            ),
+            JsCommand::bitmap_linear_xor(
+                0,
+                &JsBitVec(bits),
+                CompressionCode::Uncompressed,
+            ),
+            JsCommand::bitmap_linear_win(
+                TILE_SIZE,
+                3,
+                &JsPixelGrid(pixels),
+                CompressionCode::Zlib,
+            )
+            .unwrap(),
+        ];
+        for command in commands {
+            assert_eq!(round_trip(&command), command.0);
+        }
+    }
+
+    #[test]
+    fn bitmap_linear_win_has_to_be_aligned() {
+        assert_eq!(check_tile_aligned(0), Ok(()));
+        assert_eq!(check_tile_aligned(2 * TILE_SIZE), Ok(()));
+        assert_eq!(
+            check_tile_aligned(3),
+            Err(format!("x 3 is not a multiple of {TILE_SIZE}"))
+        );
+
+        let Ok(JsCommand(Command::BitmapLinearWin(origin, ..))) =
+            JsCommand::bitmap_linear_win(
+                8,
+                5,
+                &JsPixelGrid::max_sized(),
+                CompressionCode::Uncompressed,
+            )
+        else {
+            panic!("expected a window");
+        };
+        assert_eq!(origin, Origin(8, 5));
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        assert!(command_from_bytes(&[0, 1, 2]).is_err());
+        assert!(command_from_bytes(&[0xff; 10]).is_err());
+        assert!(command_from_bytes(&[0, 2, 0, 0, 0, 0, 0, 0, 0, 0]).is_ok());
+    }
+}
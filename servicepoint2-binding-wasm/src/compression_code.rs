@@ -0,0 +1,19 @@
+use wasm_bindgen::prelude::*;
+
+/// Specifies the kind of compression to use. Only compressions with a pure
+/// rust implementation are available in WebAssembly.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompressionCode {
+    Uncompressed,
+    Zlib,
+}
+
+impl From<CompressionCode> for servicepoint2::CompressionCode {
+    fn from(value: CompressionCode) -> Self {
+        match value {
+            CompressionCode::Uncompressed => Self::Uncompressed,
+            CompressionCode::Zlib => Self::Zlib,
+        }
+    }
+}
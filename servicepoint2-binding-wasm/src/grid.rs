@@ -0,0 +1,38 @@
+//! Bounds checks for the grids, throwing errors instead of panicking.
+
+use wasm_bindgen::JsError;
+
+pub fn check_position(
+    width: usize,
+    height: usize,
+    x: usize,
+    y: usize,
+) -> Result<(), JsError> {
+    if x < width && y < height {
+        Ok(())
+    } else {
+        Err(JsError::new(&format!(
+            "cannot access {x}-{y}, the grid is {width}x{height}"
+        )))
+    }
+}
+
+pub fn check_size(len: usize, expected: usize) -> Result<(), JsError> {
+    if len == expected {
+        Ok(())
+    } else {
+        Err(JsError::new(&format!(
+            "expected {expected} bytes, got {len}"
+        )))
+    }
+}
+
+pub fn check_multiple_of_8(name: &str, value: usize) -> Result<(), JsError> {
+    if value / 8 * 8 == value {
+        Ok(())
+    } else {
+        Err(JsError::new(&format!(
+            "{name} {value} is not a multiple of 8"
+        )))
+    }
+}
@@ -0,0 +1,73 @@
+//! WebAssembly bindings for encoding packets with the `servicepoint2` crate,
+//! built with `wasm-pack`.
+//!
+//! There is no `Connection`, as browsers cannot send UDP packets. Send the
+//! bytes of `Command.toBytes()` through a relay instead.
+
+use wasm_bindgen::prelude::*;
+
+pub use crate::bit_vec::JsBitVec;
+pub use crate::byte_grid::JsByteGrid;
+pub use crate::command::JsCommand;
+pub use crate::compression_code::CompressionCode;
+pub use crate::pixel_grid::JsPixelGrid;
+
+mod bit_vec;
+mod byte_grid;
+mod command;
+mod compression_code;
+mod grid;
+mod pixel_grid;
+
+/// The constants from `servicepoint2`, e.g. `Constants.PIXEL_WIDTH`
+#[wasm_bindgen]
+pub struct Constants;
+
+#[wasm_bindgen]
+impl Constants {
+    #[wasm_bindgen(getter = TILE_SIZE)]
+    pub fn tile_size() -> usize {
+        servicepoint2::TILE_SIZE
+    }
+
+    #[wasm_bindgen(getter = TILE_WIDTH)]
+    pub fn tile_width() -> usize {
+        servicepoint2::TILE_WIDTH
+    }
+
+    #[wasm_bindgen(getter = TILE_HEIGHT)]
+    pub fn tile_height() -> usize {
+        servicepoint2::TILE_HEIGHT
+    }
+
+    #[wasm_bindgen(getter = PIXEL_WIDTH)]
+    pub fn pixel_width() -> usize {
+        servicepoint2::PIXEL_WIDTH
+    }
+
+    #[wasm_bindgen(getter = PIXEL_HEIGHT)]
+    pub fn pixel_height() -> usize {
+        servicepoint2::PIXEL_HEIGHT
+    }
+
+    #[wasm_bindgen(getter = PIXEL_COUNT)]
+    pub fn pixel_count() -> usize {
+        servicepoint2::PIXEL_COUNT
+    }
+
+    /// in milliseconds, to be used with `setTimeout`
+    #[wasm_bindgen(getter = FRAME_PACING)]
+    pub fn frame_pacing() -> u32 {
+        servicepoint2::FRAME_PACING.as_millis() as u32
+    }
+
+    #[wasm_bindgen(getter = MIN_BRIGHTNESS)]
+    pub fn min_brightness() -> u8 {
+        servicepoint2::Brightness::MIN.into()
+    }
+
+    #[wasm_bindgen(getter = MAX_BRIGHTNESS)]
+    pub fn max_brightness() -> u8 {
+        servicepoint2::Brightness::MAX.into()
+    }
+}
@@ -0,0 +1,81 @@
+use wasm_bindgen::prelude::*;
+
+use servicepoint2::{DataRef, Grid, PixelGrid};
+
+use crate::grid::{check_multiple_of_8, check_position, check_size};
+
+/// A 2D grid of pixels, packed into bytes with the most significant bit first.
+#[wasm_bindgen(js_name = PixelGrid)]
+#[derive(Debug, Clone)]
+pub struct JsPixelGrid(pub(crate) PixelGrid);
+
+#[wasm_bindgen(js_class = PixelGrid)]
+impl JsPixelGrid {
+    /// Creates a grid with all pixels off.
+    ///
+    /// Throws if the width is not a multiple of 8.
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: usize, height: usize) -> Result<JsPixelGrid, JsError> {
+        check_multiple_of_8("width", width)?;
+        Ok(Self(PixelGrid::new(width, height)))
+    }
+
+    /// Creates a grid with the size of the whole screen.
+    #[wasm_bindgen(js_name = maxSized)]
+    pub fn max_sized() -> JsPixelGrid {
+        Self(PixelGrid::max_sized())
+    }
+
+    /// Creates a grid from a copy of the packed bytes, row by row.
+    ///
+    /// Throws if the width is not a multiple of 8 or the size does not match.
+    pub fn load(
+        width: usize,
+        height: usize,
+        data: &[u8],
+    ) -> Result<JsPixelGrid, JsError> {
+        check_multiple_of_8("width", width)?;
+        check_size(data.len(), width / 8 * height)?;
+        Ok(Self(PixelGrid::load(width, height, data)))
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Result<bool, JsError> {
+        check_position(self.0.width(), self.0.height(), x, y)?;
+        Ok(self.0.get(x, y))
+    }
+
+    /// Sets the pixel at the position and returns the old value.
+    pub fn set(
+        &mut self,
+        x: usize,
+        y: usize,
+        value: bool,
+    ) -> Result<bool, JsError> {
+        check_position(self.0.width(), self.0.height(), x, y)?;
+        Ok(self.0.set(x, y, value))
+    }
+
+    pub fn fill(&mut self, value: bool) {
+        self.0.fill(value);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> usize {
+        self.0.width()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> usize {
+        self.0.height()
+    }
+
+    /// A copy of the packed bytes, row by row
+    pub fn data(&self) -> Vec<u8> {
+        self.0.data_ref().to_vec()
+    }
+
+    #[wasm_bindgen(js_name = clone)]
+    pub fn js_clone(&self) -> JsPixelGrid {
+        self.clone()
+    }
+}
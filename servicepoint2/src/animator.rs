@@ -0,0 +1,371 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{
+    Command, CompressionCode, Connection, Origin, PixelGrid, FRAME_PACING,
+};
+
+/// When running late by more than this many frames, the missed time is not simulated.
+const MAX_CATCH_UP: u32 = 5;
+
+/// Counts of the frames of an `Animator`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    /// frames that were sent to the display
+    pub sent: u64,
+    /// frames that were simulated but not sent, because the loop was running late
+    pub dropped: u64,
+}
+
+/// Controls a running `Animator` from other threads, e.g. a Ctrl-C handler.
+#[derive(Debug, Clone, Default)]
+pub struct AnimatorHandle {
+    paused: Arc<AtomicBool>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl AnimatorHandle {
+    /// Stops calling update and sending frames until `resume` is called.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+    }
+
+    /// Continues after `pause`. The time spent paused is not simulated.
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+    }
+
+    /// Whether the animation is paused
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Makes the animation loop return after the current frame.
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the animation was stopped
+    #[must_use]
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
+    }
+}
+
+/// Runs an animation at a fixed frame rate.
+///
+/// Instead of sleeping a fixed time after each frame, the loop waits until the next frame is
+/// due, so the time needed for updating and sending does not slow the animation down. When
+/// running late, the update is called once for every missed frame, but only the last result
+/// is sent.
+///
+/// # Examples
+///
+/// ```rust
+/// use servicepoint2::{Animator, Connection, Grid, PixelGrid};
+///
+/// let connection = Connection::open("172.23.42.29:2342").unwrap();
+/// let mut animator = Animator::new();
+///
+/// let handle = animator.handle();
+/// // e.g. in a Ctrl-C handler: handle.stop();
+///
+/// let mut x = 0;
+/// let mut pixels = PixelGrid::max_sized();
+/// let stats = animator
+///     .run(&connection, &mut pixels, |pixels, _| {
+///         pixels.set(x, 0, true);
+///         x += 1;
+///         if x == 10 {
+///             handle.stop();
+///         }
+///     })
+///     .unwrap();
+/// assert!(stats.sent > 0);
+/// ```
+#[derive(Debug)]
+pub struct Animator {
+    frame_time: Duration,
+    origin: Origin,
+    compression: CompressionCode,
+    handle: AnimatorHandle,
+    stats: FrameStats,
+}
+
+impl Animator {
+    /// Creates an animator sending frames to the top left of the screen every `FRAME_PACING`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            frame_time: FRAME_PACING,
+            origin: Origin(0, 0),
+            compression: CompressionCode::Uncompressed,
+            handle: AnimatorHandle::default(),
+            stats: FrameStats::default(),
+        }
+    }
+
+    /// Sets the time between two frames.
+    ///
+    /// # Panics
+    ///
+    /// - when the frame time is zero
+    pub fn set_frame_time(&mut self, frame_time: Duration) {
+        assert!(!frame_time.is_zero(), "frame time cannot be zero");
+        self.frame_time = frame_time;
+    }
+
+    /// Sets where on screen the frames are shown.
+    /// The x-coordinate has to be dividable by 8.
+    pub fn set_origin(&mut self, origin: Origin) {
+        self.origin = origin;
+    }
+
+    /// Sets the compression used for sending frames.
+    pub fn set_compression(&mut self, compression: CompressionCode) {
+        self.compression = compression;
+    }
+
+    /// Get a handle for pausing or stopping the animation from anywhere.
+    #[must_use]
+    pub fn handle(&self) -> AnimatorHandle {
+        self.handle.clone()
+    }
+
+    /// The frame counts of all runs so far
+    #[must_use]
+    pub fn stats(&self) -> FrameStats {
+        self.stats
+    }
+
+    /// Calls `update` once per frame and sends the pixels to the display, until stopped
+    /// through the handle.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection`: where to send the frames to
+    /// * `pixels`: the frame, passed to `update` for changing it
+    /// * `update`: called with the frame and the frame time
+    ///
+    /// returns: the frame counts of this run
+    ///
+    /// # Errors
+    ///
+    /// Any errors produced while sending. The loop stops at the first error.
+    pub fn run(
+        &mut self,
+        connection: &Connection,
+        pixels: &mut PixelGrid,
+        mut update: impl FnMut(&mut PixelGrid, Duration),
+    ) -> std::io::Result<FrameStats> {
+        let mut stats = FrameStats::default();
+        let mut next_frame = Instant::now();
+
+        while !self.handle.is_stopped() {
+            if self.handle.is_paused() {
+                thread::sleep(self.frame_time);
+                next_frame = Instant::now();
+                continue;
+            }
+
+            let (simulated, following) =
+                catch_up(next_frame, Instant::now(), self.frame_time);
+            for _ in 0..=simulated {
+                update(pixels, self.frame_time);
+            }
+            if self.handle.is_stopped() {
+                break;
+            }
+
+            let command = Command::BitmapLinearWin(
+                self.origin,
+                pixels.clone(),
+                self.compression,
+            );
+            let result = connection.send(command.into());
+            stats.sent += 1;
+            stats.dropped += u64::from(simulated);
+            self.stats.sent += 1;
+            self.stats.dropped += u64::from(simulated);
+            result?;
+
+            next_frame = following;
+            thread::sleep(next_frame.saturating_duration_since(Instant::now()));
+        }
+        Ok(stats)
+    }
+}
+
+/// Decides how to continue when the frame due at `due` starts at `now`.
+///
+/// returns: the number of missed frames to simulate before the current one, at most
+/// `MAX_CATCH_UP`, and when the frame after the current one is due. Frames beyond
+/// `MAX_CATCH_UP` are skipped without being simulated.
+fn catch_up(
+    due: Instant,
+    now: Instant,
+    frame_time: Duration,
+) -> (u32, Instant) {
+    let late = now.saturating_duration_since(due);
+    let missed = u32::try_from(late.as_nanos() / frame_time.as_nanos())
+        .unwrap_or(u32::MAX);
+    (
+        missed.min(MAX_CATCH_UP),
+        due + frame_time * missed.saturating_add(1),
+    )
+}
+
+impl Default for Animator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Runs an animation on the whole screen at `FRAME_PACING` until an error occurs.
+///
+/// Use an `Animator` for more control, e.g. to stop or pause the loop.
+///
+/// # Errors
+///
+/// Any errors produced while sending.
+pub fn run_loop(
+    connection: &Connection,
+    update: impl FnMut(&mut PixelGrid, Duration),
+) -> std::io::Result<FrameStats> {
+    Animator::new().run(connection, &mut PixelGrid::max_sized(), update)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::UdpSocket;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use super::{catch_up, MAX_CATCH_UP};
+    use crate::{Animator, Connection, Grid, PixelGrid};
+
+    fn local_connection() -> (UdpSocket, Connection) {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let connection =
+            Connection::open(socket.local_addr().unwrap()).unwrap();
+        (socket, connection)
+    }
+
+    #[test]
+    fn stop_from_update() {
+        let (socket, connection) = local_connection();
+        let mut animator = Animator::new();
+        animator.set_frame_time(Duration::from_millis(1));
+        let handle = animator.handle();
+
+        let mut updates = 0;
+        let stats = animator
+            .run(&connection, &mut PixelGrid::new(8, 1), |pixels, dt| {
+                assert_eq!(dt, Duration::from_millis(1));
+                updates += 1;
+                pixels.set(0, 0, updates % 2 == 1);
+                if updates == 5 {
+                    handle.stop();
+                }
+            })
+            .unwrap();
+
+        // frames simulated in the same iteration as the stop are not counted
+        assert!(stats.sent > 0);
+        assert!(stats.sent + stats.dropped <= 4);
+        assert_eq!(animator.stats(), stats);
+
+        let mut buffer = [0u8; 64];
+        socket.set_nonblocking(true).unwrap();
+        let mut received = 0;
+        while socket.recv(&mut buffer).is_ok() {
+            received += 1;
+        }
+        assert_eq!(received, stats.sent);
+    }
+
+    #[test]
+    fn on_time() {
+        let frame_time = Duration::from_millis(20);
+        let due = Instant::now();
+        assert_eq!(catch_up(due, due, frame_time), (0, due + frame_time));
+
+        // starting early does not move the schedule
+        let later = due + Duration::from_millis(5);
+        assert_eq!(catch_up(later, due, frame_time), (0, later + frame_time));
+
+        let slightly_late = due + Duration::from_millis(19);
+        assert_eq!(
+            catch_up(due, slightly_late, frame_time),
+            (0, due + frame_time)
+        );
+    }
+
+    #[test]
+    fn simulates_missed_frames() {
+        let frame_time = Duration::from_millis(20);
+        let due = Instant::now();
+        let late = due + Duration::from_millis(50);
+        assert_eq!(catch_up(due, late, frame_time), (2, due + frame_time * 3));
+    }
+
+    #[test]
+    fn skips_frames_beyond_catch_up() {
+        let frame_time = Duration::from_millis(20);
+        let due = Instant::now();
+        let late = due + frame_time * 40;
+        assert_eq!(
+            catch_up(due, late, frame_time),
+            (MAX_CATCH_UP, due + frame_time * 41)
+        );
+    }
+
+    #[test]
+    fn dropped_frames_were_simulated() {
+        let (_socket, connection) = local_connection();
+        let mut animator = Animator::new();
+        animator.set_frame_time(Duration::from_millis(2));
+        let handle = animator.handle();
+
+        let mut updates = 0;
+        let stats = animator
+            .run(&connection, &mut PixelGrid::new(8, 1), |_, _| {
+                updates += 1;
+                if updates == 1 {
+                    // at least MAX_CATCH_UP frames late, however slow the machine is
+                    thread::sleep(Duration::from_millis(40));
+                }
+                if updates == 20 {
+                    handle.stop();
+                }
+            })
+            .unwrap();
+
+        assert!(stats.dropped >= u64::from(MAX_CATCH_UP), "{stats:?}");
+        assert!(stats.sent + stats.dropped <= updates, "{stats:?}");
+    }
+
+    #[test]
+    fn stopped_while_paused() {
+        let (_socket, connection) = local_connection();
+        let mut animator = Animator::new();
+        let handle = animator.handle();
+        handle.pause();
+        assert!(handle.is_paused());
+
+        let stopper = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            handle.stop();
+        });
+        let stats = animator
+            .run(&connection, &mut PixelGrid::new(8, 1), |_, _| {
+                panic!("update called while paused")
+            })
+            .unwrap();
+        stopper.join().unwrap();
+        assert_eq!(stats.sent, 0);
+    }
+}
@@ -0,0 +1,374 @@
+use std::fmt::{Display, Formatter};
+
+use crate::random::XorShift;
+use crate::{ByteGrid, Command, Grid, Origin, TILE_HEIGHT, TILE_WIDTH};
+
+/// A brightness value the display supports, from `Brightness::MIN` to `Brightness::MAX`.
+///
+/// # Examples
+///
+/// ```rust
+/// use servicepoint2::Brightness;
+///
+/// let brightness = Brightness::try_from(7).unwrap();
+/// assert_eq!(u8::from(brightness), 7);
+///
+/// assert_eq!(Brightness::try_from(42), Err(()));
+/// assert_eq!(Brightness::saturating_from(42), Brightness::MAX);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Brightness(u8);
+
+impl Brightness {
+    /// the darkest brightness, the tiles are still slightly visible
+    pub const MIN: Brightness = Brightness(0);
+    /// the brightest brightness
+    pub const MAX: Brightness = Brightness(11);
+
+    /// Creates a brightness from a byte.
+    ///
+    /// returns: `None` for values above `Brightness::MAX`
+    #[must_use]
+    pub const fn new(value: u8) -> Option<Self> {
+        if value > Self::MAX.0 {
+            None
+        } else {
+            Some(Self(value))
+        }
+    }
+
+    /// Creates a brightness from a byte, using `Brightness::MAX` for values that are too big.
+    #[must_use]
+    pub const fn saturating_from(value: u8) -> Self {
+        if value > Self::MAX.0 {
+            Self::MAX
+        } else {
+            Self(value)
+        }
+    }
+
+    /// The brightness that is `progress` of the way from `self` to `other`, rounded to the
+    /// nearest value.
+    ///
+    /// `progress` is clamped to `0.0..=1.0`.
+    #[must_use]
+    pub fn interpolate(self, other: Brightness, progress: f64) -> Self {
+        let progress = progress.clamp(0.0, 1.0);
+        let value = f64::from(self.0)
+            + (f64::from(other.0) - f64::from(self.0)) * progress;
+        Self::saturating_from(value.round() as u8)
+    }
+}
+
+impl Default for Brightness {
+    fn default() -> Self {
+        Self::MAX
+    }
+}
+
+impl TryFrom<u8> for Brightness {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::new(value).ok_or(())
+    }
+}
+
+impl From<Brightness> for u8 {
+    fn from(brightness: Brightness) -> Self {
+        brightness.0
+    }
+}
+
+impl Display for Brightness {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A byte that is too big to be a `Brightness`.
+///
+/// # Examples
+///
+/// ```rust
+/// use servicepoint2::{Brightness, BrightnessOutOfRange};
+///
+/// let error = Brightness::new(12).ok_or(BrightnessOutOfRange(12));
+/// assert_eq!(
+///     error.unwrap_err().to_string(),
+///     "brightness 12 is higher than the maximum of 11"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BrightnessOutOfRange(pub u8);
+
+impl Display for BrightnessOutOfRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "brightness {} is higher than the maximum of {}",
+            self.0,
+            Brightness::MAX
+        )
+    }
+}
+
+impl std::error::Error for BrightnessOutOfRange {}
+
+/// The brightness of every tile of the screen, sized `TILE_WIDTH` x `TILE_HEIGHT`.
+///
+/// Sent as a `Command::CharBrightness` covering the whole screen.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "net", doc = "```rust")]
+#[cfg_attr(not(feature = "net"), doc = "```rust,ignore")]
+/// use servicepoint2::{Brightness, BrightnessMap, Connection};
+///
+/// let connection = Connection::open("172.23.42.29:2342").unwrap();
+/// let from = BrightnessMap::uniform(Brightness::MAX);
+/// let to = BrightnessMap::radial_gradient(Brightness::MAX, Brightness::MIN);
+///
+/// for map in from.fade(&to, 5) {
+///     connection.send(map.command().into()).unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrightnessMap(ByteGrid);
+
+impl BrightnessMap {
+    /// Creates a map with the same brightness everywhere.
+    #[must_use]
+    pub fn uniform(brightness: Brightness) -> Self {
+        Self::from_fn(|_, _| brightness)
+    }
+
+    /// Creates a map going from `left` at the left edge to `right` at the right edge.
+    #[must_use]
+    pub fn horizontal_gradient(left: Brightness, right: Brightness) -> Self {
+        Self::from_fn(|x, _| {
+            left.interpolate(right, x as f64 / (TILE_WIDTH - 1) as f64)
+        })
+    }
+
+    /// Creates a map going from `top` at the top edge to `bottom` at the bottom edge.
+    #[must_use]
+    pub fn vertical_gradient(top: Brightness, bottom: Brightness) -> Self {
+        Self::from_fn(|_, y| {
+            top.interpolate(bottom, y as f64 / (TILE_HEIGHT - 1) as f64)
+        })
+    }
+
+    /// Creates a map going from `center` in the middle of the screen to `edge` in the
+    /// corners.
+    #[must_use]
+    pub fn radial_gradient(center: Brightness, edge: Brightness) -> Self {
+        Self::from_fn(|x, y| center.interpolate(edge, distance_to_center(x, y)))
+    }
+
+    /// Creates a map with `center` on most of the screen, smoothly changing to `edge` towards
+    /// the corners.
+    ///
+    /// In contrast to `radial_gradient`, the inner half of the screen is not affected.
+    #[must_use]
+    pub fn vignette(center: Brightness, edge: Brightness) -> Self {
+        Self::from_fn(|x, y| {
+            let t = ((distance_to_center(x, y) - 0.5) * 2.0).clamp(0.0, 1.0);
+            let smooth = t * t * (3.0 - 2.0 * t);
+            center.interpolate(edge, smooth)
+        })
+    }
+
+    /// Creates a map with a random brightness from `min` to `max` for every tile.
+    ///
+    /// The same seed always results in the same map.
+    #[must_use]
+    pub fn noise(min: Brightness, max: Brightness, seed: u64) -> Self {
+        let (min, max) = (min.min(max), min.max(max));
+        let mut random = XorShift::new(seed);
+        Self::from_fn(|_, _| {
+            let range = u64::from(max.0 - min.0) + 1;
+            Brightness(min.0 + random.below(range) as u8)
+        })
+    }
+
+    /// Get the brightness of the tile at the position
+    ///
+    /// # Panics
+    ///
+    /// - when the position is outside of the screen
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> Brightness {
+        Brightness::saturating_from(self.0.get(x, y))
+    }
+
+    /// Sets the brightness of the tile at the position
+    ///
+    /// # Panics
+    ///
+    /// - when the position is outside of the screen
+    pub fn set(&mut self, x: usize, y: usize, brightness: Brightness) {
+        self.0.set(x, y, brightness.0);
+    }
+
+    /// The map that is `progress` of the way from `self` to `other`, tile by tile.
+    ///
+    /// `progress` is clamped to `0.0..=1.0`.
+    #[must_use]
+    pub fn interpolate(&self, other: &BrightnessMap, progress: f64) -> Self {
+        Self::from_fn(|x, y| {
+            self.get(x, y).interpolate(other.get(x, y), progress)
+        })
+    }
+
+    /// Smoothly changes from `self` to `to` in the specified number of steps.
+    ///
+    /// The iterator does not include `self`, but the last map is `to`.
+    pub fn fade(
+        &self,
+        to: &BrightnessMap,
+        steps: usize,
+    ) -> impl Iterator<Item = BrightnessMap> {
+        let (from, to) = (self.clone(), to.clone());
+        (1..=steps)
+            .map(move |step| from.interpolate(&to, step as f64 / steps as f64))
+    }
+
+    /// The command for showing the map on the display.
+    #[must_use]
+    pub fn command(&self) -> Command {
+        Command::CharBrightness(Origin(0, 0), self.0.clone())
+    }
+
+    fn from_fn(mut brightness: impl FnMut(usize, usize) -> Brightness) -> Self {
+        let mut grid = ByteGrid::new(TILE_WIDTH, TILE_HEIGHT);
+        for y in 0..TILE_HEIGHT {
+            for x in 0..TILE_WIDTH {
+                grid.set(x, y, brightness(x, y).0);
+            }
+        }
+        Self(grid)
+    }
+}
+
+impl Default for BrightnessMap {
+    fn default() -> Self {
+        Self::uniform(Brightness::default())
+    }
+}
+
+impl From<BrightnessMap> for ByteGrid {
+    fn from(map: BrightnessMap) -> Self {
+        map.0
+    }
+}
+
+/// The distance of the tile to the middle of the screen, 0.0 in the middle and 1.0 in the
+/// corners
+fn distance_to_center(x: usize, y: usize) -> f64 {
+    let center_x = (TILE_WIDTH - 1) as f64 / 2.0;
+    let center_y = (TILE_HEIGHT - 1) as f64 / 2.0;
+    let dx = x as f64 - center_x;
+    let dy = y as f64 - center_y;
+    (dx * dx + dy * dy).sqrt() / center_x.hypot(center_y)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Brightness, BrightnessMap, ByteGrid, Command, Grid, Origin,
+        TILE_HEIGHT, TILE_WIDTH,
+    };
+
+    #[test]
+    fn checked() {
+        assert_eq!(Brightness::new(0), Some(Brightness::MIN));
+        assert_eq!(Brightness::new(11), Some(Brightness::MAX));
+        assert_eq!(Brightness::new(12), None);
+        assert_eq!(Brightness::try_from(5).map(u8::from), Ok(5));
+        assert_eq!(Brightness::try_from(255), Err(()));
+    }
+
+    #[test]
+    fn saturating() {
+        assert_eq!(u8::from(Brightness::saturating_from(5)), 5);
+        assert_eq!(Brightness::saturating_from(255), Brightness::MAX);
+        assert_eq!(u8::from(Brightness::MIN), 0);
+        assert_eq!(Brightness::default(), Brightness::MAX);
+    }
+
+    #[test]
+    fn interpolate_brightness() {
+        let low = Brightness::saturating_from(2);
+        let high = Brightness::saturating_from(10);
+        assert_eq!(low.interpolate(high, 0.5), Brightness::saturating_from(6));
+        assert_eq!(high.interpolate(low, 0.25), Brightness::saturating_from(8));
+        assert_eq!(low.interpolate(high, 2.0), high);
+    }
+
+    #[test]
+    fn gradients() {
+        let map = BrightnessMap::horizontal_gradient(
+            Brightness::MIN,
+            Brightness::MAX,
+        );
+        assert_eq!(map.get(0, 7), Brightness::MIN);
+        assert_eq!(map.get(TILE_WIDTH - 1, 7), Brightness::MAX);
+        assert!(map.get(20, 0) < map.get(30, 0));
+
+        let map =
+            BrightnessMap::vertical_gradient(Brightness::MAX, Brightness::MIN);
+        assert_eq!(map.get(3, 0), Brightness::MAX);
+        assert_eq!(map.get(3, TILE_HEIGHT - 1), Brightness::MIN);
+
+        let map =
+            BrightnessMap::radial_gradient(Brightness::MAX, Brightness::MIN);
+        assert_eq!(map.get(0, 0), Brightness::MIN);
+        assert_eq!(map.get(TILE_WIDTH - 1, TILE_HEIGHT - 1), Brightness::MIN);
+        assert_eq!(map.get(TILE_WIDTH / 2, TILE_HEIGHT / 2), Brightness::MAX);
+    }
+
+    #[test]
+    fn vignette_keeps_center() {
+        let map = BrightnessMap::vignette(Brightness::MAX, Brightness::MIN);
+        assert_eq!(map.get(0, 0), Brightness::MIN);
+        assert_eq!(map.get(TILE_WIDTH / 2, 0), Brightness::MAX);
+        assert_eq!(map.get(20, 10), Brightness::MAX);
+    }
+
+    #[test]
+    fn noise_in_range() {
+        let min = Brightness::saturating_from(3);
+        let max = Brightness::saturating_from(7);
+        let map = BrightnessMap::noise(max, min, 1);
+        for y in 0..TILE_HEIGHT {
+            for x in 0..TILE_WIDTH {
+                assert!((min..=max).contains(&map.get(x, y)));
+            }
+        }
+        assert_eq!(map, BrightnessMap::noise(min, max, 1));
+        assert_ne!(map, BrightnessMap::noise(min, max, 2));
+    }
+
+    #[test]
+    fn fade_ends_at_target() {
+        let from = BrightnessMap::uniform(Brightness::MIN);
+        let to = BrightnessMap::uniform(Brightness::MAX);
+        let steps: Vec<BrightnessMap> = from.fade(&to, 4).collect();
+        assert_eq!(steps.len(), 4);
+        assert_eq!(steps[0].get(5, 5), Brightness::saturating_from(3));
+        assert_eq!(steps[3], to);
+    }
+
+    #[test]
+    fn command_covers_screen() {
+        let mut map = BrightnessMap::uniform(Brightness::MIN);
+        map.set(1, 2, Brightness::MAX);
+        let Command::CharBrightness(Origin(0, 0), grid) = map.command() else {
+            panic!("unexpected command");
+        };
+        assert_eq!((grid.width(), grid.height()), (TILE_WIDTH, TILE_HEIGHT));
+        assert_eq!(grid.get(1, 2), 11);
+        assert_eq!(ByteGrid::from(map), grid);
+    }
+}
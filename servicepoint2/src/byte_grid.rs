@@ -1,4 +1,6 @@
-use crate::{DataRef, Grid};
+use std::fmt::{Display, Formatter};
+
+use crate::{cp437_to_char, DataRef, Grid};
 
 /// A 2D grid of bytes
 #[derive(Debug, Clone, PartialEq)]
@@ -101,6 +103,21 @@ impl DataRef for ByteGrid {
     }
 }
 
+impl Display for ByteGrid {
+    /// Renders the bytes as rows of CP437 decoded text.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (index, row) in self.data.chunks(self.width.max(1)).enumerate() {
+            if index != 0 {
+                writeln!(f)?;
+            }
+            for byte in row {
+                write!(f, "{}", cp437_to_char(*byte))?;
+            }
+        }
+        Ok(())
+    }
+}
+
 impl From<ByteGrid> for Vec<u8> {
     /// Turn into the underlying `Vec<u8>` containing the rows of bytes.
     fn from(value: ByteGrid) -> Self {
@@ -276,4 +293,10 @@ mod tests {
         assert_eq!(vec.data, [1, 2, 3, 4]);
         assert_eq!(vec.get(1, 0), 2)
     }
+
+    #[test]
+    fn display_cp437() {
+        let grid = ByteGrid::load(3, 2, &[b'a', 0x84, 0xDB, 0, b'!', 0x01]);
+        assert_eq!(grid.to_string(), "aä█\n !☺");
+    }
 }
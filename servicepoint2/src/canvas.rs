@@ -0,0 +1,480 @@
+#[cfg(feature = "net")]
+use std::thread;
+
+use crate::{
+    char_to_cp437, Area, Brightness, ByteGrid, Command, CompressionCode, Grid,
+    Origin, PixelGrid, TILE_SIZE,
+};
+#[cfg(feature = "net")]
+use crate::{Connection, FRAME_PACING};
+
+/// A drawing surface that remembers what was sent to the display, so only changes have to
+/// be sent.
+///
+/// The canvas covers an `Area` of the display. Pixels are addressed relative to the top left
+/// of that area, text and brightness in tiles relative to it.
+///
+/// On the display, a tile either shows text or pixels. Tiles with a character other than `0`
+/// show the character, all other tiles show the pixels.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "net", doc = "```rust")]
+#[cfg_attr(not(feature = "net"), doc = "```rust,ignore")]
+/// use servicepoint2::{Canvas, Connection, Grid};
+///
+/// let connection = Connection::open("172.23.42.29:2342").unwrap();
+/// let mut canvas = Canvas::new();
+///
+/// canvas.pixels_mut().set(100, 42, true);
+/// canvas.write_text(2, 1, "Hello");
+/// canvas.flush(&connection).unwrap();
+///
+/// // only sends the tile containing the pixel
+/// canvas.pixels_mut().set(100, 42, false);
+/// canvas.flush(&connection).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Canvas {
+    area: Area,
+    pixels: PixelGrid,
+    chars: ByteGrid,
+    brightness: ByteGrid,
+    compression: CompressionCode,
+    /// the contents at the last flush, `None` if unknown
+    sent: Option<Sent>,
+}
+
+#[derive(Debug, Clone)]
+struct Sent {
+    pixels: PixelGrid,
+    chars: ByteGrid,
+    brightness: ByteGrid,
+}
+
+impl Canvas {
+    /// Creates a canvas covering the whole screen.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::for_area(Area::FULL_SCREEN)
+    }
+
+    /// Creates a canvas covering the area of the screen.
+    ///
+    /// # Panics
+    ///
+    /// - when the area is empty or not completely on the screen
+    #[must_use]
+    pub fn for_area(area: Area) -> Self {
+        assert!(area.width > 0 && area.height > 0, "area cannot be empty");
+        assert!(
+            Area::FULL_SCREEN.contains(&area),
+            "area has to be on the screen"
+        );
+
+        let mut brightness = ByteGrid::new(area.width, area.height);
+        brightness.fill(Brightness::default().into());
+        Self {
+            area,
+            pixels: PixelGrid::new(
+                area.width * TILE_SIZE,
+                area.height * TILE_SIZE,
+            ),
+            chars: ByteGrid::new(area.width, area.height),
+            brightness,
+            compression: CompressionCode::Uncompressed,
+            sent: None,
+        }
+    }
+
+    /// The area of the screen this canvas covers
+    #[must_use]
+    pub fn area(&self) -> Area {
+        self.area
+    }
+
+    /// The pixels, shown in all tiles without text
+    #[must_use]
+    pub fn pixels(&self) -> &PixelGrid {
+        &self.pixels
+    }
+
+    /// The pixels for drawing, shown in all tiles without text
+    pub fn pixels_mut(&mut self) -> &mut PixelGrid {
+        &mut self.pixels
+    }
+
+    /// The CP437 encoded text, one byte per tile
+    #[must_use]
+    pub fn chars(&self) -> &ByteGrid {
+        &self.chars
+    }
+
+    /// The CP437 encoded text for editing, one byte per tile
+    pub fn chars_mut(&mut self) -> &mut ByteGrid {
+        &mut self.chars
+    }
+
+    /// The brightness, one byte per tile
+    #[must_use]
+    pub fn brightness(&self) -> &ByteGrid {
+        &self.brightness
+    }
+
+    /// The brightness for editing, one byte per tile
+    pub fn brightness_mut(&mut self) -> &mut ByteGrid {
+        &mut self.brightness
+    }
+
+    /// Sets the compression used for sending pixels.
+    pub fn set_compression(&mut self, compression: CompressionCode) {
+        self.compression = compression;
+    }
+
+    /// Writes the text into a row of tiles, starting at the specified tile.
+    ///
+    /// Characters without a CP437 representation are replaced with `?`. Text that does not
+    /// fit is cut off.
+    pub fn write_text(&mut self, x: usize, y: usize, text: &str) {
+        if y >= self.chars.height() {
+            return;
+        }
+        let columns = x..self.chars.width();
+        for (column, char) in columns.zip(text.chars()) {
+            let byte = char_to_cp437(char).unwrap_or(b'?');
+            self.chars.set(column, y, byte);
+        }
+    }
+
+    /// Turns off all pixels and removes all text. The brightness stays unchanged.
+    pub fn clear(&mut self) {
+        self.pixels.fill(false);
+        self.chars.fill(0);
+    }
+
+    /// Forgets what was sent before, so the next flush sends everything.
+    ///
+    /// Use this when the display may have been changed by someone else, e.g. after a reboot.
+    pub fn invalidate(&mut self) {
+        self.sent = None;
+    }
+
+    /// Sends the changes since the last flush to the display.
+    ///
+    /// Waits `FRAME_PACING` between packets so the display does not drop them.
+    ///
+    /// # Errors
+    ///
+    /// Any errors produced while sending. In that case, the next flush sends everything.
+    #[cfg(feature = "net")]
+    pub fn flush(&mut self, connection: &Connection) -> std::io::Result<()> {
+        for (index, command) in self.take_changes().into_iter().enumerate() {
+            if index > 0 {
+                thread::sleep(FRAME_PACING);
+            }
+            if let Err(err) = connection.send(command.into()) {
+                self.invalidate();
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Computes the commands needed to bring the display up to date and assumes they will be
+    /// sent.
+    ///
+    /// This is what `flush` sends. Use it directly for sending through something other than a
+    /// `Connection`.
+    ///
+    /// returns: the commands in the order they have to be sent in
+    pub fn take_changes(&mut self) -> Vec<Command> {
+        let mut commands = vec![];
+        let pixels_area = self.pixel_changes();
+        if let Some(area) = pixels_area {
+            let pixels = self.pixels.window(
+                area.x * TILE_SIZE,
+                area.y * TILE_SIZE,
+                area.width * TILE_SIZE,
+                area.height * TILE_SIZE,
+            );
+            let Origin(x, y) = self.screen_origin(area);
+            commands.push(Command::BitmapLinearWin(
+                Origin(x * TILE_SIZE, y * TILE_SIZE),
+                pixels,
+                self.compression,
+            ));
+        }
+
+        self.text_changes(pixels_area, &mut commands);
+        if let Some(command) = self.brightness_change() {
+            commands.push(command);
+        }
+
+        self.sent = Some(Sent {
+            pixels: self.pixels.clone(),
+            chars: self.chars.clone(),
+            brightness: self.brightness.clone(),
+        });
+        commands
+    }
+
+    /// The tiles showing pixels that are different on the display
+    fn pixel_changes(&self) -> Option<Area> {
+        let mut dirty = ByteGrid::new(self.area.width, self.area.height);
+        for y in 0..self.area.height {
+            for x in 0..self.area.width {
+                if self.chars.get(x, y) != 0 {
+                    continue;
+                }
+                let changed = match &self.sent {
+                    None => true,
+                    Some(sent) => {
+                        sent.chars.get(x, y) != 0
+                            || tile_differs(&sent.pixels, &self.pixels, x, y)
+                    }
+                };
+                dirty.set(x, y, u8::from(changed));
+            }
+        }
+        bounding_box(&dirty)
+    }
+
+    /// Text that is different on the display or was just overwritten by pixels, sent in runs
+    /// of consecutive tiles so that tiles without text are not touched.
+    fn text_changes(
+        &self,
+        pixels_area: Option<Area>,
+        commands: &mut Vec<Command>,
+    ) {
+        let overwritten = |x: usize, y: usize| {
+            pixels_area.is_some_and(|area| {
+                area.contains(&Area {
+                    x,
+                    y,
+                    width: 1,
+                    height: 1,
+                })
+            })
+        };
+
+        for y in 0..self.area.height {
+            let mut run_start = None;
+            for x in 0..=self.area.width {
+                let changed = x < self.area.width
+                    && self.chars.get(x, y) != 0
+                    && (overwritten(x, y)
+                        || match &self.sent {
+                            None => true,
+                            Some(sent) => {
+                                sent.chars.get(x, y) != self.chars.get(x, y)
+                            }
+                        });
+                match (changed, run_start) {
+                    (true, None) => run_start = Some(x),
+                    (false, Some(start)) => {
+                        run_start = None;
+                        let run = Area {
+                            x: start,
+                            y,
+                            width: x - start,
+                            height: 1,
+                        };
+                        commands.push(Command::Cp437Data(
+                            self.screen_origin(run),
+                            self.chars.window(start, y, x - start, 1),
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn brightness_change(&self) -> Option<Command> {
+        let mut dirty = ByteGrid::new(self.area.width, self.area.height);
+        for y in 0..self.area.height {
+            for x in 0..self.area.width {
+                let changed = match &self.sent {
+                    None => true,
+                    Some(sent) => {
+                        sent.brightness.get(x, y) != self.brightness.get(x, y)
+                    }
+                };
+                dirty.set(x, y, u8::from(changed));
+            }
+        }
+        let area = bounding_box(&dirty)?;
+
+        let first = self.brightness.get(0, 0);
+        let uniform = (0..self.area.height).all(|y| {
+            (0..self.area.width).all(|x| self.brightness.get(x, y) == first)
+        });
+        if uniform && self.area == Area::FULL_SCREEN {
+            return Some(Command::Brightness(Brightness::saturating_from(
+                first,
+            )));
+        }
+
+        Some(Command::CharBrightness(
+            self.screen_origin(area),
+            self.brightness
+                .window(area.x, area.y, area.width, area.height),
+        ))
+    }
+
+    /// Converts the top left of an area on the canvas to tile coordinates on the screen.
+    fn screen_origin(&self, area: Area) -> Origin {
+        Origin(self.area.x + area.x, self.area.y + area.y)
+    }
+}
+
+impl Default for Canvas {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn tile_differs(a: &PixelGrid, b: &PixelGrid, x: usize, y: usize) -> bool {
+    (0..TILE_SIZE).any(|pixel_y| {
+        (0..TILE_SIZE).any(|pixel_x| {
+            let (x, y) = (x * TILE_SIZE + pixel_x, y * TILE_SIZE + pixel_y);
+            a.get(x, y) != b.get(x, y)
+        })
+    })
+}
+
+/// The smallest area containing all non-zero cells
+fn bounding_box(grid: &ByteGrid) -> Option<Area> {
+    let (mut min_x, mut min_y) = (usize::MAX, usize::MAX);
+    let (mut max_x, mut max_y) = (0, 0);
+    for y in 0..grid.height() {
+        for x in 0..grid.width() {
+            if grid.get(x, y) != 0 {
+                min_x = min_x.min(x);
+                min_y = min_y.min(y);
+                max_x = max_x.max(x);
+                max_y = max_y.max(y);
+            }
+        }
+    }
+    if min_x == usize::MAX {
+        return None;
+    }
+    Some(Area {
+        x: min_x,
+        y: min_y,
+        width: max_x - min_x + 1,
+        height: max_y - min_y + 1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Area, Brightness, ByteGrid, Canvas, Command, CompressionCode, Grid,
+        Origin, PixelGrid,
+    };
+
+    #[test]
+    fn first_flush_sends_everything() {
+        let mut canvas = Canvas::new();
+        let commands = canvas.take_changes();
+        assert_eq!(
+            commands,
+            vec![
+                Command::BitmapLinearWin(
+                    Origin(0, 0),
+                    PixelGrid::max_sized(),
+                    CompressionCode::Uncompressed
+                ),
+                Command::Brightness(Brightness::MAX),
+            ]
+        );
+        assert_eq!(canvas.take_changes(), vec![]);
+
+        canvas.invalidate();
+        assert_eq!(canvas.take_changes().len(), 2);
+    }
+
+    #[test]
+    fn only_changed_tiles() {
+        let mut canvas = Canvas::new();
+        canvas.take_changes();
+
+        canvas.pixels_mut().set(17, 9, true);
+        canvas.pixels_mut().set(30, 10, true);
+        let mut expected = PixelGrid::new(16, 8);
+        expected.set(1, 1, true);
+        expected.set(14, 2, true);
+        assert_eq!(
+            canvas.take_changes(),
+            vec![Command::BitmapLinearWin(
+                Origin(16, 8),
+                expected,
+                CompressionCode::Uncompressed
+            )]
+        );
+    }
+
+    #[test]
+    fn text_over_pixels() {
+        let mut canvas = Canvas::new();
+        canvas.take_changes();
+
+        canvas.write_text(1, 2, "ab d");
+        assert_eq!(
+            canvas.take_changes(),
+            vec![Command::Cp437Data(
+                Origin(1, 2),
+                ByteGrid::load(4, 1, b"ab d")
+            ),]
+        );
+
+        // pixels next to the text have to be sent, and the overwritten text again
+        canvas.pixels_mut().set(0, 16, true);
+        canvas.pixels_mut().set(47, 16, true);
+        let commands = canvas.take_changes();
+        assert_eq!(commands.len(), 2);
+        assert!(matches!(
+            &commands[0],
+            Command::BitmapLinearWin(Origin(0, 16), pixels, _)
+                if pixels.width() == 48
+        ));
+        assert_eq!(
+            commands[1],
+            Command::Cp437Data(Origin(1, 2), ByteGrid::load(4, 1, b"ab d"))
+        );
+
+        // removed text shows the pixels again
+        canvas.chars_mut().set(2, 2, 0);
+        assert!(matches!(
+            canvas.take_changes()[..],
+            [Command::BitmapLinearWin(Origin(16, 16), ..)]
+        ));
+    }
+
+    #[test]
+    fn area_and_brightness() {
+        let mut canvas = Canvas::for_area(Area {
+            x: 4,
+            y: 2,
+            width: 3,
+            height: 2,
+        });
+        assert_eq!(canvas.pixels().width(), 24);
+        canvas.take_changes();
+
+        canvas.brightness_mut().set(1, 1, 5);
+        canvas.write_text(2, 0, "xyz");
+        assert_eq!(
+            canvas.take_changes(),
+            vec![
+                Command::Cp437Data(Origin(6, 2), ByteGrid::load(1, 1, b"x")),
+                Command::CharBrightness(
+                    Origin(5, 3),
+                    ByteGrid::load(1, 1, &[5])
+                ),
+            ]
+        );
+    }
+}
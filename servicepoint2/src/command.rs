@@ -1,12 +1,15 @@
+use std::fmt::{Display, Formatter};
+
 use crate::command_code::CommandCode;
 use crate::compression::{into_compressed, into_decompressed};
 use crate::{
-    BitVec, ByteGrid, CompressionCode, Grid, Header, Packet, PixelGrid,
-    TILE_SIZE,
+    BitVec, Brightness, ByteGrid, CompressionCode, DataRef, Grid, Header,
+    LinearHeader, Packet, PixelGrid, TileHeader, WindowHeader, TILE_SIZE,
 };
 
 /// An origin marks the top left position of a window sent to the display.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Origin(pub usize, pub usize);
 
 impl std::ops::Add<Origin> for Origin {
@@ -22,11 +25,9 @@ impl std::ops::Add<Origin> for Origin {
 /// Type alias for documenting the meaning of the u16 in enum values
 pub type Offset = usize;
 
-/// Type alias for documenting the meaning of the u16 in enum values
-pub type Brightness = u8;
-
 /// A command to send to the display.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Command {
     /// Set all pixels to the off state
     Clear,
@@ -59,6 +60,37 @@ pub enum Command {
     BitmapLinearWin(Origin, PixelGrid, CompressionCode),
 }
 
+/// The kind of a `Command`, without any of its data.
+///
+/// Useful for matching rules against commands, e.g. in a `Policy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommandKind {
+    /// `Command::Clear`
+    Clear,
+    /// `Command::HardReset`
+    HardReset,
+    /// `Command::FadeOut`
+    FadeOut,
+    /// `Command::CharBrightness`
+    CharBrightness,
+    /// `Command::Brightness`
+    Brightness,
+    /// `Command::BitmapLegacy`
+    BitmapLegacy,
+    /// `Command::BitmapLinear`
+    BitmapLinear,
+    /// `Command::BitmapLinearAnd`
+    BitmapLinearAnd,
+    /// `Command::BitmapLinearOr`
+    BitmapLinearOr,
+    /// `Command::BitmapLinearXor`
+    BitmapLinearXor,
+    /// `Command::Cp437Data`
+    Cp437Data,
+    /// `Command::BitmapLinearWin`, regardless of the compression
+    BitmapLinearWin,
+}
+
 impl From<Command> for Packet {
     /// Move the `Command` into a `Packet` instance for sending.
     #[allow(clippy::cast_possible_truncation)]
@@ -75,16 +107,9 @@ impl From<Command> for Packet {
             Command::BitmapLegacy => {
                 Command::command_code_only(CommandCode::BitmapLegacy)
             }
-            Command::CharBrightness(Origin(x, y), grid) => Packet(
-                Header(
-                    CommandCode::CharBrightness.into(),
-                    x as u16,
-                    y as u16,
-                    grid.width() as u16,
-                    grid.height() as u16,
-                ),
-                grid.into(),
-            ),
+            Command::CharBrightness(origin, grid) => {
+                tile_grid_into_packet(CommandCode::CharBrightness, origin, grid)
+            }
             Command::Brightness(brightness) => Packet(
                 Header(
                     CommandCode::Brightness.into(),
@@ -93,7 +118,7 @@ impl From<Command> for Packet {
                     0x0000,
                     0x0000,
                 ),
-                vec![brightness],
+                vec![brightness.into()],
             ),
             Command::BitmapLinearWin(origin, pixels, compression) => {
                 bitmap_win_into_packet(origin, pixels, compression)
@@ -130,16 +155,9 @@ impl From<Command> for Packet {
                     bits.into(),
                 )
             }
-            Command::Cp437Data(Origin(x, y), grid) => Packet(
-                Header(
-                    CommandCode::Cp437Data.into(),
-                    x as u16,
-                    y as u16,
-                    grid.width() as u16,
-                    grid.height() as u16,
-                ),
-                grid.into(),
-            ),
+            Command::Cp437Data(origin, grid) => {
+                tile_grid_into_packet(CommandCode::Cp437Data, origin, grid)
+            }
         }
     }
 }
@@ -158,7 +176,21 @@ fn bitmap_win_into_packet(
     let tile_w = (pixels.width() / TILE_SIZE) as u16;
     let pixel_h = pixels.height() as u16;
     let payload = into_compressed(compression, pixels.into());
-    let command = match compression {
+    let command = bitmap_win_command_code(compression);
+
+    let header = WindowHeader {
+        command_code: command.into(),
+        tile_x,
+        pixel_y: pixel_y as u16,
+        tile_w,
+        pixel_h,
+    };
+    Packet(header.into(), payload)
+}
+
+/// Selects the `BitmapLinearWin*` command code for the compression
+fn bitmap_win_command_code(compression: CompressionCode) -> CommandCode {
+    match compression {
         CompressionCode::Uncompressed => {
             CommandCode::BitmapLinearWinUncompressed
         }
@@ -170,12 +202,94 @@ fn bitmap_win_into_packet(
         CompressionCode::Lzma => CommandCode::BitmapLinearWinLzma,
         #[cfg(feature = "compression_zstd")]
         CompressionCode::Zstd => CommandCode::BitmapLinearWinZstd,
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn tile_grid_into_packet(
+    command: CommandCode,
+    origin: Origin,
+    grid: ByteGrid,
+) -> Packet {
+    let Origin(tile_x, tile_y) = origin;
+    let header = TileHeader {
+        command_code: command.into(),
+        tile_x: tile_x as u16,
+        tile_y: tile_y as u16,
+        tile_w: grid.width() as u16,
+        tile_h: grid.height() as u16,
     };
+    Packet(header.into(), grid.into())
+}
 
-    Packet(
-        Header(command.into(), tile_x, pixel_y as u16, tile_w, pixel_h),
-        payload,
-    )
+impl Display for Command {
+    /// Writes a one-line summary of the command.
+    ///
+    /// Use the alternate flag (`{:#}`) to additionally render the contents of grids.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let code = self.command_code();
+        write!(f, "{code:?} [0x{:04x}]", u16::from(code))?;
+        match self {
+            Command::Clear | Command::HardReset | Command::FadeOut => Ok(()),
+            #[allow(deprecated)]
+            Command::BitmapLegacy => Ok(()),
+            Command::Brightness(brightness) => write!(f, " value {brightness}"),
+            Command::CharBrightness(Origin(x, y), grid) => {
+                write!(
+                    f,
+                    " origin ({x}, {y}) size {}x{} tiles",
+                    grid.width(),
+                    grid.height()
+                )?;
+                if f.alternate() {
+                    for y in 0..grid.height() {
+                        writeln!(f)?;
+                        for x in 0..grid.width() {
+                            match grid.get(x, y) {
+                                value @ 0..=0xF => write!(f, "{value:x}")?,
+                                _ => write!(f, "#")?,
+                            }
+                        }
+                    }
+                }
+                Ok(())
+            }
+            Command::Cp437Data(Origin(x, y), grid) => {
+                write!(
+                    f,
+                    " origin ({x}, {y}) size {}x{} tiles",
+                    grid.width(),
+                    grid.height()
+                )?;
+                if f.alternate() {
+                    write!(f, "\n{grid}")?;
+                }
+                Ok(())
+            }
+            Command::BitmapLinear(offset, bits, compression)
+            | Command::BitmapLinearAnd(offset, bits, compression)
+            | Command::BitmapLinearOr(offset, bits, compression)
+            | Command::BitmapLinearXor(offset, bits, compression) => write!(
+                f,
+                " offset {offset} length {} bits ({} bytes) compression {compression:?}",
+                bits.len(),
+                bits.len() / 8
+            ),
+            Command::BitmapLinearWin(Origin(x, y), pixels, compression) => {
+                write!(
+                    f,
+                    " origin ({x}, {y}) size {}x{} pixels ({} bytes) compression {compression:?}",
+                    pixels.width(),
+                    pixels.height(),
+                    pixels.width() * pixels.height() / 8
+                )?;
+                if f.alternate() {
+                    write!(f, "\n{pixels}")?;
+                }
+                Ok(())
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -194,6 +308,8 @@ pub enum TryFromPacketError {
     InvalidCompressionCode(u16),
     /// Decompression of the payload failed. This can be caused by corrupted packets.
     DecompressionFailed,
+    /// The contained brightness value is higher than `Brightness::MAX`.
+    InvalidBrightness(u8),
 }
 
 impl TryFrom<Packet> for Command {
@@ -201,7 +317,7 @@ impl TryFrom<Packet> for Command {
 
     /// Try to interpret the `Packet` as one containing a `Command`
     fn try_from(packet: Packet) -> Result<Self, Self::Error> {
-        let Packet(Header(command_u16, a, b, c, d), _) = packet;
+        let Packet(Header(command_u16, ..), _) = packet;
         let command_code = match CommandCode::try_from(command_u16) {
             Err(()) => {
                 return Err(TryFromPacketError::InvalidCommand(command_u16));
@@ -227,7 +343,12 @@ impl TryFrom<Packet> for Command {
                 if a != 0 || b != 0 || c != 0 || d != 0 {
                     Err(TryFromPacketError::ExtraneousHeaderValues)
                 } else {
-                    Ok(Command::Brightness(payload[0]))
+                    match Brightness::try_from(payload[0]) {
+                        Ok(brightness) => Ok(Command::Brightness(brightness)),
+                        Err(()) => Err(TryFromPacketError::InvalidBrightness(
+                            payload[0],
+                        )),
+                    }
                 }
             }
             CommandCode::HardReset => match Self::check_command_only(packet) {
@@ -239,40 +360,41 @@ impl TryFrom<Packet> for Command {
                 None => Ok(Command::FadeOut),
             },
             CommandCode::Cp437Data => {
-                let Packet(_, payload) = packet;
-                Ok(Command::Cp437Data(
-                    Origin(a as usize, b as usize),
-                    ByteGrid::load(c as usize, d as usize, &payload),
-                ))
+                let (origin, grid) = Self::packet_into_tile_grid(packet)?;
+                Ok(Command::Cp437Data(origin, grid))
             }
             CommandCode::CharBrightness => {
-                let Packet(_, payload) = packet;
-                Ok(Command::CharBrightness(
-                    Origin(a as usize, b as usize),
-                    ByteGrid::load(c as usize, d as usize, &payload),
-                ))
+                let (origin, grid) = Self::packet_into_tile_grid(packet)?;
+                let invalid = grid
+                    .data_ref()
+                    .iter()
+                    .find(|value| Brightness::new(**value).is_none());
+                if let Some(value) = invalid {
+                    return Err(TryFromPacketError::InvalidBrightness(*value));
+                }
+                Ok(Command::CharBrightness(origin, grid))
             }
             #[allow(deprecated)]
             CommandCode::BitmapLegacy => Ok(Command::BitmapLegacy),
             CommandCode::BitmapLinear => {
-                let (vec, compression) =
+                let (offset, vec, compression) =
                     Self::packet_into_linear_bitmap(packet)?;
-                Ok(Command::BitmapLinear(a as Offset, vec, compression))
+                Ok(Command::BitmapLinear(offset, vec, compression))
             }
             CommandCode::BitmapLinearAnd => {
-                let (vec, compression) =
+                let (offset, vec, compression) =
                     Self::packet_into_linear_bitmap(packet)?;
-                Ok(Command::BitmapLinearAnd(a as Offset, vec, compression))
+                Ok(Command::BitmapLinearAnd(offset, vec, compression))
             }
             CommandCode::BitmapLinearOr => {
-                let (vec, compression) =
+                let (offset, vec, compression) =
                     Self::packet_into_linear_bitmap(packet)?;
-                Ok(Command::BitmapLinearOr(a as Offset, vec, compression))
+                Ok(Command::BitmapLinearOr(offset, vec, compression))
             }
             CommandCode::BitmapLinearXor => {
-                let (vec, compression) =
+                let (offset, vec, compression) =
                     Self::packet_into_linear_bitmap(packet)?;
-                Ok(Command::BitmapLinearXor(a as Offset, vec, compression))
+                Ok(Command::BitmapLinearXor(offset, vec, compression))
             }
             CommandCode::BitmapLinearWinUncompressed => {
                 Self::packet_into_bitmap_win(
@@ -301,20 +423,74 @@ impl TryFrom<Packet> for Command {
 }
 
 impl Command {
+    /// The kind of this command
+    #[must_use]
+    pub fn kind(&self) -> CommandKind {
+        match self {
+            Command::Clear => CommandKind::Clear,
+            Command::HardReset => CommandKind::HardReset,
+            Command::FadeOut => CommandKind::FadeOut,
+            Command::CharBrightness(_, _) => CommandKind::CharBrightness,
+            Command::Brightness(_) => CommandKind::Brightness,
+            #[allow(deprecated)]
+            Command::BitmapLegacy => CommandKind::BitmapLegacy,
+            Command::BitmapLinear(_, _, _) => CommandKind::BitmapLinear,
+            Command::BitmapLinearAnd(_, _, _) => CommandKind::BitmapLinearAnd,
+            Command::BitmapLinearOr(_, _, _) => CommandKind::BitmapLinearOr,
+            Command::BitmapLinearXor(_, _, _) => CommandKind::BitmapLinearXor,
+            Command::Cp437Data(_, _) => CommandKind::Cp437Data,
+            Command::BitmapLinearWin(_, _, _) => CommandKind::BitmapLinearWin,
+        }
+    }
+
+    /// The `CommandCode` the command gets encoded with
+    fn command_code(&self) -> CommandCode {
+        match self {
+            Command::Clear => CommandCode::Clear,
+            Command::HardReset => CommandCode::HardReset,
+            Command::FadeOut => CommandCode::FadeOut,
+            Command::CharBrightness(_, _) => CommandCode::CharBrightness,
+            Command::Brightness(_) => CommandCode::Brightness,
+            #[allow(deprecated)]
+            Command::BitmapLegacy => CommandCode::BitmapLegacy,
+            Command::BitmapLinear(_, _, _) => CommandCode::BitmapLinear,
+            Command::BitmapLinearAnd(_, _, _) => CommandCode::BitmapLinearAnd,
+            Command::BitmapLinearOr(_, _, _) => CommandCode::BitmapLinearOr,
+            Command::BitmapLinearXor(_, _, _) => CommandCode::BitmapLinearXor,
+            Command::Cp437Data(_, _) => CommandCode::Cp437Data,
+            Command::BitmapLinearWin(_, _, compression) => {
+                bitmap_win_command_code(*compression)
+            }
+        }
+    }
+
     fn packet_into_bitmap_win(
         packet: Packet,
         compression: CompressionCode,
     ) -> Result<Command, TryFromPacketError> {
-        let Packet(Header(_, tiles_x, pixels_y, tile_w, pixel_h), payload) =
-            packet;
+        let Packet(header, payload) = packet;
+        let WindowHeader {
+            tile_x,
+            pixel_y,
+            tile_w,
+            pixel_h,
+            ..
+        } = header.into();
 
         let payload = match into_decompressed(compression, payload) {
             None => return Err(TryFromPacketError::DecompressionFailed),
             Some(decompressed) => decompressed,
         };
+        let expected = tile_w as usize * pixel_h as usize;
+        if payload.len() != expected {
+            return Err(TryFromPacketError::UnexpectedPayloadSize(
+                expected,
+                payload.len(),
+            ));
+        }
 
         Ok(Command::BitmapLinearWin(
-            Origin(tiles_x as usize * TILE_SIZE, pixels_y as usize),
+            Origin(tile_x as usize * TILE_SIZE, pixel_y as usize),
             PixelGrid::load(
                 tile_w as usize * TILE_SIZE,
                 pixel_h as usize,
@@ -334,16 +510,39 @@ impl Command {
     ) -> Packet {
         let length = payload.len() as u16;
         let payload = into_compressed(compression, payload);
-        Packet(
-            Header(
-                command.into(),
-                offset as u16,
-                length,
-                compression.into(),
-                0,
-            ),
-            payload,
-        )
+        let header = LinearHeader {
+            command_code: command.into(),
+            offset: offset as u16,
+            length,
+            compression: compression.into(),
+            reserved: 0,
+        };
+        Packet(header.into(), payload)
+    }
+
+    /// Helper method for Packets into `Cp437Data` and `CharBrightness`
+    fn packet_into_tile_grid(
+        packet: Packet,
+    ) -> Result<(Origin, ByteGrid), TryFromPacketError> {
+        let Packet(header, payload) = packet;
+        let TileHeader {
+            tile_x,
+            tile_y,
+            tile_w,
+            tile_h,
+            ..
+        } = header.into();
+        let expected = tile_w as usize * tile_h as usize;
+        if payload.len() != expected {
+            return Err(TryFromPacketError::UnexpectedPayloadSize(
+                expected,
+                payload.len(),
+            ));
+        }
+        Ok((
+            Origin(tile_x as usize, tile_y as usize),
+            ByteGrid::load(tile_w as usize, tile_h as usize, &payload),
+        ))
     }
 
     /// Helper method for creating empty packets only containing the command code
@@ -366,8 +565,15 @@ impl Command {
     /// Helper method for Packets into `BitMapLinear*`-Commands
     fn packet_into_linear_bitmap(
         packet: Packet,
-    ) -> Result<(BitVec, CompressionCode), TryFromPacketError> {
-        let Packet(Header(_, _, length, sub, reserved), payload) = packet;
+    ) -> Result<(Offset, BitVec, CompressionCode), TryFromPacketError> {
+        let Packet(header, payload) = packet;
+        let LinearHeader {
+            offset,
+            length,
+            compression: sub,
+            reserved,
+            ..
+        } = header.into();
         if reserved != 0 {
             return Err(TryFromPacketError::ExtraneousHeaderValues);
         }
@@ -387,7 +593,7 @@ impl Command {
                 payload.len(),
             ));
         }
-        Ok((BitVec::from(&*payload), sub))
+        Ok((offset as Offset, BitVec::from(&*payload), sub))
     }
 }
 
@@ -440,12 +646,31 @@ pub mod c_api {
         Box::into_raw(Box::new(Command::FadeOut))
     }
 
-    /// Allocates a new `Command::Brightness` instance
+    /// Allocates a new `Command::Brightness` instance.
+    ///
+    /// Values above the maximum brightness are clamped.
     #[no_mangle]
     pub unsafe extern "C" fn sp2_command_brightness(
-        brightness: Brightness,
+        brightness: u8,
     ) -> *mut Command {
-        Box::into_raw(Box::new(Command::Brightness(brightness)))
+        Box::into_raw(Box::new(Command::Brightness(
+            Brightness::saturating_from(brightness),
+        )))
+    }
+
+    /// Allocates a new `Command::Brightness` instance.
+    ///
+    /// returns: NULL if the brightness is higher than the maximum of 11
+    #[no_mangle]
+    pub unsafe extern "C" fn sp2_command_brightness_checked(
+        brightness: u8,
+    ) -> *mut Command {
+        match Brightness::new(brightness) {
+            None => null_mut(),
+            Some(brightness) => {
+                Box::into_raw(Box::new(Command::Brightness(brightness)))
+            }
+        }
     }
 
     /// Allocates a new `Command::CharBrightness` instance.
@@ -569,8 +794,8 @@ mod tests {
     use crate::command::TryFromPacketError;
     use crate::command_code::CommandCode;
     use crate::{
-        BitVec, ByteGrid, Command, CompressionCode, Grid, Header, Origin,
-        Packet, PixelGrid,
+        BitVec, Brightness, ByteGrid, Command, CompressionCode, Grid, Header,
+        LinearHeader, Origin, Packet, PixelGrid,
     };
 
     fn round_trip(original: Command) {
@@ -613,7 +838,7 @@ mod tests {
 
     #[test]
     fn round_trip_brightness() {
-        round_trip(Command::Brightness(6));
+        round_trip(Command::Brightness(Brightness::saturating_from(6)));
     }
 
     #[test]
@@ -802,6 +1027,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn invalid_brightness() {
+        assert_eq!(
+            Command::try_from(Packet(
+                Header(CommandCode::Brightness.into(), 0, 0, 0, 0),
+                vec!(12),
+            )),
+            Err(TryFromPacketError::InvalidBrightness(12))
+        );
+
+        assert_eq!(
+            Command::try_from(Packet(
+                Header(CommandCode::CharBrightness.into(), 0, 0, 2, 1),
+                vec!(11, 255),
+            )),
+            Err(TryFromPacketError::InvalidBrightness(255))
+        );
+    }
+
+    #[test]
+    fn unexpected_payload_size_grids() {
+        assert_eq!(
+            Command::try_from(Packet(
+                Header(CommandCode::Cp437Data.into(), 0, 0, 3, 2),
+                vec![0; 5],
+            )),
+            Err(TryFromPacketError::UnexpectedPayloadSize(6, 5))
+        );
+
+        assert_eq!(
+            Command::try_from(Packet(
+                Header(CommandCode::CharBrightness.into(), 0, 0, 1, 1),
+                vec![],
+            )),
+            Err(TryFromPacketError::UnexpectedPayloadSize(1, 0))
+        );
+
+        assert_eq!(
+            Command::try_from(Packet(
+                Header(
+                    CommandCode::BitmapLinearWinUncompressed.into(),
+                    0,
+                    0,
+                    2,
+                    3
+                ),
+                vec![0; 7],
+            )),
+            Err(TryFromPacketError::UnexpectedPayloadSize(6, 7))
+        );
+    }
+
     #[test]
     fn error_reserved_used() {
         let Packet(header, payload) = Command::BitmapLinear(
@@ -810,8 +1087,11 @@ mod tests {
             CompressionCode::Uncompressed,
         )
         .into();
-        let Header(command, offset, length, sub, _reserved) = header;
-        let p = Packet(Header(command, offset, length, sub, 69), payload);
+        let header = LinearHeader {
+            reserved: 69,
+            ..header.into()
+        };
+        let p = Packet(header.into(), payload);
         assert_eq!(
             Command::try_from(p),
             Err(TryFromPacketError::ExtraneousHeaderValues)
@@ -826,8 +1106,11 @@ mod tests {
             CompressionCode::Uncompressed,
         )
         .into();
-        let Header(command, offset, length, _sub, reserved) = header;
-        let p = Packet(Header(command, offset, length, 42, reserved), payload);
+        let header = LinearHeader {
+            compression: 42,
+            ..header.into()
+        };
+        let p = Packet(header.into(), payload);
         assert_eq!(
             Command::try_from(p),
             Err(TryFromPacketError::InvalidCompressionCode(42))
@@ -842,20 +1125,68 @@ mod tests {
             CompressionCode::Uncompressed,
         )
         .into();
-        let Header(command, offset, length, compression, reserved) = header;
+        let header = LinearHeader::from(header);
         let p = Packet(
-            Header(command, offset, 420, compression, reserved),
+            LinearHeader {
+                length: 420,
+                ..header
+            }
+            .into(),
             payload,
         );
         assert_eq!(
             Command::try_from(p),
             Err(TryFromPacketError::UnexpectedPayloadSize(
                 420,
-                length as usize,
+                header.length as usize,
             ))
         );
     }
 
+    #[test]
+    fn display_summary() {
+        assert_eq!(Command::Clear.to_string(), "Clear [0x0002]");
+        assert_eq!(
+            Command::Brightness(Brightness::saturating_from(5)).to_string(),
+            "Brightness [0x0007] value 5"
+        );
+        assert_eq!(
+            Command::BitmapLinearWin(
+                Origin(8, 2),
+                PixelGrid::new(16, 4),
+                CompressionCode::Uncompressed
+            )
+            .to_string(),
+            "BitmapLinearWinUncompressed [0x0013] origin (8, 2) size 16x4 pixels (8 bytes) compression Uncompressed"
+        );
+        assert_eq!(
+            Command::BitmapLinearXor(
+                3,
+                BitVec::new(16),
+                CompressionCode::Uncompressed
+            )
+            .to_string(),
+            "BitmapLinearXor [0x0016] offset 3 length 16 bits (2 bytes) compression Uncompressed"
+        );
+    }
+
+    #[test]
+    fn display_alternate_renders_grid() {
+        let mut grid = ByteGrid::new(2, 1);
+        grid.set(0, 0, b'h');
+        grid.set(1, 0, b'i');
+        assert_eq!(
+            format!("{:#}", Command::Cp437Data(Origin(0, 0), grid.clone())),
+            "Cp437Data [0x0003] origin (0, 0) size 2x1 tiles\nhi"
+        );
+
+        grid.set(1, 0, 0x0B);
+        assert_eq!(
+            format!("{:#}", Command::CharBrightness(Origin(0, 0), grid)),
+            "CharBrightness [0x0005] origin (0, 0) size 2x1 tiles\n#b"
+        );
+    }
+
     #[test]
     fn origin_add() {
         assert_eq!(Origin(4, 2), Origin(1, 0) + Origin(3, 2));
@@ -1,6 +1,7 @@
 /// Specifies the kind of compression to use. Availability depends on features.
 #[repr(u16)]
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CompressionCode {
     Uncompressed = 0x0,
     #[cfg(feature = "compression_zlib")]
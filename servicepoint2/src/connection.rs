@@ -1,13 +1,20 @@
 use std::fmt::Debug;
 use std::net::{ToSocketAddrs, UdpSocket};
+use std::sync::{Mutex, PoisonError};
 
 use log::{debug, info};
 
 use crate::Packet;
 
+/// Called with every packet before it is sent
+type Tap = Box<dyn Fn(&Packet) + Send + Sync>;
+
 /// A connection to the display.
 pub struct Connection {
     socket: UdpSocket,
+    /// reused for serializing packets to avoid allocating for every frame
+    buffer: Mutex<Vec<u8>>,
+    tap: Option<Tap>,
 }
 
 impl Connection {
@@ -28,7 +35,18 @@ impl Connection {
         info!("connecting to {addr:?}");
         let socket = UdpSocket::bind("0.0.0.0:0")?;
         socket.connect(addr)?;
-        Ok(Self { socket })
+        Ok(Self {
+            socket,
+            buffer: Mutex::new(vec![]),
+            tap: None,
+        })
+    }
+
+    /// Calls the function with every packet before it is sent, e.g. for showing a preview.
+    ///
+    /// Replaces the previous function.
+    pub fn set_tap(&mut self, tap: impl Fn(&Packet) + Send + Sync + 'static) {
+        self.tap = Some(Box::new(tap));
     }
 
     /// Send something packet-like to the display. Usually this is in the form of a Command.
@@ -64,8 +82,14 @@ impl Connection {
     /// ```
     pub fn send(&self, packet: Packet) -> Result<(), std::io::Error> {
         debug!("sending {packet:?}");
-        let data: Vec<u8> = packet.into();
-        self.socket.send(&data)?;
+        if let Some(tap) = &self.tap {
+            tap(&packet);
+        }
+        // the buffer gets cleared before each use, so a poisoned lock is harmless
+        let mut buffer =
+            self.buffer.lock().unwrap_or_else(PoisonError::into_inner);
+        packet.write_into(&mut buffer);
+        self.socket.send(&buffer)?;
         Ok(())
     }
 }
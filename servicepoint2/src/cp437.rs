@@ -0,0 +1,170 @@
+use std::fmt::{Display, Formatter};
+
+use crate::{ByteGrid, Grid};
+
+/// Unicode representation of all CP437 code points, as they are rendered by the display.
+///
+/// The control characters in `0x00..0x20` are mapped to their graphical glyphs, except for `0x00`
+/// which is shown as a space.
+#[rustfmt::skip]
+const CP437_TO_UNICODE: [char; 256] = [
+    // 0x00
+    ' ', '☺', '☻', '♥', '♦', '♣', '♠', '•', '◘', '○', '◙', '♂', '♀', '♪', '♫', '☼',
+    // 0x10
+    '►', '◄', '↕', '‼', '¶', '§', '▬', '↨', '↑', '↓', '→', '←', '∟', '↔', '▲', '▼',
+    // 0x20
+    ' ', '!', '"', '#', '$', '%', '&', '\'', '(', ')', '*', '+', ',', '-', '.', '/',
+    // 0x30
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', ':', ';', '<', '=', '>', '?',
+    // 0x40
+    '@', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O',
+    // 0x50
+    'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '[', '\\', ']', '^', '_',
+    // 0x60
+    '`', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o',
+    // 0x70
+    'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '{', '|', '}', '~', '⌂',
+    // 0x80
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å',
+    // 0x90
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ',
+    // 0xA0
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»',
+    // 0xB0
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐',
+    // 0xC0
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧',
+    // 0xD0
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀',
+    // 0xE0
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩',
+    // 0xF0
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{a0}',
+];
+
+/// Decodes a single CP437 byte to the corresponding unicode `char`.
+///
+/// # Examples
+///
+/// ```
+/// use servicepoint2::cp437_to_char;
+/// assert_eq!(cp437_to_char(0x41), 'A');
+/// assert_eq!(cp437_to_char(0xDB), '█');
+/// ```
+#[must_use]
+pub fn cp437_to_char(byte: u8) -> char {
+    CP437_TO_UNICODE[byte as usize]
+}
+
+/// Encodes a unicode `char` as a single CP437 byte.
+///
+/// returns: `None` if the character cannot be represented in CP437
+///
+/// # Examples
+///
+/// ```
+/// use servicepoint2::char_to_cp437;
+/// assert_eq!(char_to_cp437('A'), Some(0x41));
+/// assert_eq!(char_to_cp437('ä'), Some(0x84));
+/// assert_eq!(char_to_cp437('€'), None);
+/// ```
+#[must_use]
+pub fn char_to_cp437(value: char) -> Option<u8> {
+    if (' '..='~').contains(&value) {
+        return Some(value as u8);
+    }
+
+    CP437_TO_UNICODE
+        .iter()
+        .position(|c| *c == value)
+        .map(|index| index as u8)
+}
+
+/// A character that cannot be represented in CP437.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotCp437(pub char);
+
+impl Display for NotCp437 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}' cannot be shown, it is not part of CP437", self.0)
+    }
+}
+
+impl std::error::Error for NotCp437 {}
+
+/// Encodes text as a grid of CP437 characters with one row per line.
+///
+/// Lines are separated by `\n` and short lines are padded with spaces.
+///
+/// returns: the first character that cannot be represented in CP437 as error
+///
+/// # Examples
+///
+/// ```
+/// use servicepoint2::{text_to_cp437, Grid, NotCp437};
+/// let grid = text_to_cp437("Hello,\nCCCB").unwrap();
+/// assert_eq!((grid.width(), grid.height()), (6, 2));
+/// assert_eq!(grid.get(4, 1), b' ');
+/// assert_eq!(text_to_cp437("5€"), Err(NotCp437('€')));
+/// ```
+pub fn text_to_cp437(text: &str) -> Result<ByteGrid, NotCp437> {
+    let lines: Vec<&str> = text.lines().collect();
+    let width = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut grid = ByteGrid::new(width, lines.len());
+    grid.fill(b' ');
+    for (y, line) in lines.iter().enumerate() {
+        for (x, char) in line.chars().enumerate() {
+            grid.set(x, y, char_to_cp437(char).ok_or(NotCp437(char))?);
+        }
+    }
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{char_to_cp437, cp437_to_char, text_to_cp437, Grid, NotCp437};
+
+    #[test]
+    fn ascii_is_identity() {
+        for byte in 0x20u8..0x7F {
+            assert_eq!(cp437_to_char(byte), byte as char);
+            assert_eq!(char_to_cp437(byte as char), Some(byte));
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for byte in 0x01u8..=0xFF {
+            assert_eq!(char_to_cp437(cp437_to_char(byte)), Some(byte));
+        }
+    }
+
+    #[test]
+    fn text() {
+        let grid = text_to_cp437("ab\n\nä").unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid.get(1, 0), b'b');
+        assert_eq!(grid.get(0, 1), b' ');
+        assert_eq!(grid.get(0, 2), 0x84);
+
+        let grid = text_to_cp437("").unwrap();
+        assert_eq!((grid.width(), grid.height()), (0, 0));
+
+        let error = text_to_cp437("a\n€").unwrap_err();
+        assert_eq!(error, NotCp437('€'));
+        assert_eq!(
+            error.to_string(),
+            "'€' cannot be shown, it is not part of CP437"
+        );
+    }
+
+    #[test]
+    fn null_is_space() {
+        assert_eq!(cp437_to_char(0), ' ');
+        assert_eq!(char_to_cp437(' '), Some(0x20));
+    }
+}
@@ -0,0 +1,182 @@
+use crate::Header;
+
+/// The header of a `BitmapLinearWin` command, which addresses a window in tile columns and
+/// pixel rows.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowHeader {
+    /// The raw command code
+    pub command_code: u16,
+    /// The left edge of the window in tiles
+    pub tile_x: u16,
+    /// The top edge of the window in pixels
+    pub pixel_y: u16,
+    /// The width of the window in tiles
+    pub tile_w: u16,
+    /// The height of the window in pixels
+    pub pixel_h: u16,
+}
+
+/// The header of the `Cp437Data` and `CharBrightness` commands, which address a window in tiles.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TileHeader {
+    /// The raw command code
+    pub command_code: u16,
+    /// The left edge of the window in tiles
+    pub tile_x: u16,
+    /// The top edge of the window in tiles
+    pub tile_y: u16,
+    /// The width of the window in tiles
+    pub tile_w: u16,
+    /// The height of the window in tiles
+    pub tile_h: u16,
+}
+
+/// The header of the `BitmapLinear*` commands, which address a range of pixels.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearHeader {
+    /// The raw command code
+    pub command_code: u16,
+    /// The offset of the first pixel on screen
+    pub offset: u16,
+    /// The length of the uncompressed payload in bytes
+    pub length: u16,
+    /// The raw `CompressionCode` of the payload
+    pub compression: u16,
+    /// Unused, has to be 0
+    pub reserved: u16,
+}
+
+impl From<Header> for WindowHeader {
+    fn from(value: Header) -> Self {
+        let Header(command_code, tile_x, pixel_y, tile_w, pixel_h) = value;
+        Self {
+            command_code,
+            tile_x,
+            pixel_y,
+            tile_w,
+            pixel_h,
+        }
+    }
+}
+
+impl From<WindowHeader> for Header {
+    fn from(value: WindowHeader) -> Self {
+        let WindowHeader {
+            command_code,
+            tile_x,
+            pixel_y,
+            tile_w,
+            pixel_h,
+        } = value;
+        Header(command_code, tile_x, pixel_y, tile_w, pixel_h)
+    }
+}
+
+impl From<Header> for TileHeader {
+    fn from(value: Header) -> Self {
+        let Header(command_code, tile_x, tile_y, tile_w, tile_h) = value;
+        Self {
+            command_code,
+            tile_x,
+            tile_y,
+            tile_w,
+            tile_h,
+        }
+    }
+}
+
+impl From<TileHeader> for Header {
+    fn from(value: TileHeader) -> Self {
+        let TileHeader {
+            command_code,
+            tile_x,
+            tile_y,
+            tile_w,
+            tile_h,
+        } = value;
+        Header(command_code, tile_x, tile_y, tile_w, tile_h)
+    }
+}
+
+impl From<Header> for LinearHeader {
+    fn from(value: Header) -> Self {
+        let Header(command_code, offset, length, compression, reserved) =
+            value;
+        Self {
+            command_code,
+            offset,
+            length,
+            compression,
+            reserved,
+        }
+    }
+}
+
+impl From<LinearHeader> for Header {
+    fn from(value: LinearHeader) -> Self {
+        let LinearHeader {
+            command_code,
+            offset,
+            length,
+            compression,
+            reserved,
+        } = value;
+        Header(command_code, offset, length, compression, reserved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Header, LinearHeader, TileHeader, WindowHeader};
+
+    #[test]
+    fn window_round_trip() {
+        let header = WindowHeader::from(Header(0x13, 1, 2, 3, 4));
+        assert_eq!(
+            header,
+            WindowHeader {
+                command_code: 0x13,
+                tile_x: 1,
+                pixel_y: 2,
+                tile_w: 3,
+                pixel_h: 4,
+            }
+        );
+        assert_eq!(Header::from(header), Header(0x13, 1, 2, 3, 4));
+    }
+
+    #[test]
+    fn tile_round_trip() {
+        let header = TileHeader::from(Header(0x03, 1, 2, 3, 4));
+        assert_eq!(
+            header,
+            TileHeader {
+                command_code: 0x03,
+                tile_x: 1,
+                tile_y: 2,
+                tile_w: 3,
+                tile_h: 4,
+            }
+        );
+        assert_eq!(Header::from(header), Header(0x03, 1, 2, 3, 4));
+    }
+
+    #[test]
+    fn linear_round_trip() {
+        let header = LinearHeader::from(Header(0x12, 1, 2, 3, 4));
+        assert_eq!(
+            header,
+            LinearHeader {
+                command_code: 0x12,
+                offset: 1,
+                length: 2,
+                compression: 3,
+                reserved: 4,
+            }
+        );
+        assert_eq!(Header::from(header), Header(0x12, 1, 2, 3, 4));
+    }
+}
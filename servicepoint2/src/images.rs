@@ -0,0 +1,463 @@
+use std::io::{BufRead, Cursor, Seek};
+use std::path::Path;
+#[cfg(feature = "net")]
+use std::thread;
+use std::time::Duration;
+
+use image::codecs::gif::GifDecoder;
+use image::imageops::FilterType;
+use image::{
+    AnimationDecoder, DynamicImage, GrayImage, ImageFormat, ImageReader,
+    ImageResult,
+};
+
+#[cfg(feature = "net")]
+use crate::{Command, Connection, FRAME_PACING};
+use crate::{
+    CompressionCode, Grid, Origin, PixelGrid, PIXEL_HEIGHT, PIXEL_WIDTH,
+};
+
+/// How an image is scaled to the requested size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Resize {
+    /// scale to fit completely, keeping the aspect ratio. The rest stays off.
+    #[default]
+    Fit,
+    /// scale to cover everything, keeping the aspect ratio. The overlapping parts are cut off.
+    Fill,
+    /// scale to exactly the requested size, ignoring the aspect ratio
+    Stretch,
+}
+
+/// How gray pixels are turned into on and off
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Dithering {
+    /// every pixel brighter than the threshold is on
+    None,
+    /// a regular 4x4 pattern, looks calm in animations
+    Ordered,
+    /// error diffusion, shows the most detail in still images
+    #[default]
+    FloydSteinberg,
+}
+
+/// Options for converting images into `PixelGrid`s
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageOptions {
+    /// the width of the result in pixels, has to be dividable by 8
+    pub width: usize,
+    /// the height of the result in pixels
+    pub height: usize,
+    /// how the image is scaled
+    pub resize: Resize,
+    /// how gray pixels are turned into on and off
+    pub dithering: Dithering,
+    /// brightness from 0 to 255 above which pixels are on, used by `Dithering::None`
+    pub threshold: u8,
+    /// turn dark pixels on instead of bright ones
+    pub invert: bool,
+}
+
+impl Default for ImageOptions {
+    /// Options for converting to the size of the whole screen
+    fn default() -> Self {
+        Self {
+            width: PIXEL_WIDTH,
+            height: PIXEL_HEIGHT,
+            resize: Resize::Fit,
+            dithering: Dithering::FloydSteinberg,
+            threshold: 128,
+            invert: false,
+        }
+    }
+}
+
+/// A single image of an animation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    /// the converted image
+    pub pixels: PixelGrid,
+    /// how long the frame is shown, zero for still images
+    pub delay: Duration,
+}
+
+/// Converts an image into pixels by resizing and dithering it.
+///
+/// Transparent parts count as black.
+///
+/// # Panics
+///
+/// - when the width in the options is not dividable by 8
+#[must_use]
+pub fn convert_image(
+    image: &DynamicImage,
+    options: &ImageOptions,
+) -> PixelGrid {
+    let (width, height) = (options.width as u32, options.height as u32);
+    let resized = match options.resize {
+        Resize::Fit => image.resize(width, height, FilterType::Triangle),
+        Resize::Fill => {
+            image.resize_to_fill(width, height, FilterType::Triangle)
+        }
+        Resize::Stretch => {
+            image.resize_exact(width, height, FilterType::Triangle)
+        }
+    };
+
+    // center on a black background of the requested size
+    let gray = luma_over_black(&resized);
+    let mut canvas = GrayImage::new(width, height);
+    let x = i64::from(width.saturating_sub(gray.width()) / 2);
+    let y = i64::from(height.saturating_sub(gray.height()) / 2);
+    image::imageops::replace(&mut canvas, &gray, x, y);
+    if options.invert {
+        image::imageops::invert(&mut canvas);
+    }
+
+    dither(&canvas, options)
+}
+
+/// Loads an image file and converts it into pixels.
+///
+/// Of animated images, only the first frame is loaded.
+///
+/// # Errors
+///
+/// When the file could not be read or decoded.
+pub fn load_image(
+    path: impl AsRef<Path>,
+    options: &ImageOptions,
+) -> ImageResult<PixelGrid> {
+    let image = ImageReader::open(path)?.with_guessed_format()?.decode()?;
+    Ok(convert_image(&image, options))
+}
+
+/// Loads all frames of an image file, e.g. a GIF animation.
+///
+/// Still images result in a single frame without delay.
+///
+/// # Errors
+///
+/// When the file could not be read or decoded.
+pub fn load_frames(
+    path: impl AsRef<Path>,
+    options: &ImageOptions,
+) -> ImageResult<Vec<Frame>> {
+    read_frames(ImageReader::open(path)?.with_guessed_format()?, options)
+}
+
+/// Decodes all frames of an image in memory, e.g. a GIF animation.
+///
+/// Still images result in a single frame without delay.
+///
+/// # Errors
+///
+/// When the data could not be decoded.
+pub fn decode_frames(
+    data: &[u8],
+    options: &ImageOptions,
+) -> ImageResult<Vec<Frame>> {
+    let reader = ImageReader::new(Cursor::new(data)).with_guessed_format()?;
+    read_frames(reader, options)
+}
+
+fn read_frames<R: BufRead + Seek>(
+    reader: ImageReader<R>,
+    options: &ImageOptions,
+) -> ImageResult<Vec<Frame>> {
+    if reader.format() != Some(ImageFormat::Gif) {
+        let image = reader.decode()?;
+        return Ok(vec![Frame {
+            pixels: convert_image(&image, options),
+            delay: Duration::ZERO,
+        }]);
+    }
+
+    let decoder = GifDecoder::new(reader.into_inner())?;
+    decoder
+        .into_frames()
+        .map(|frame| {
+            let frame = frame?;
+            let (numerator, denominator) = frame.delay().numer_denom_ms();
+            let delay = Duration::from_micros(
+                u64::from(numerator) * 1000 / u64::from(denominator.max(1)),
+            );
+            let image = DynamicImage::ImageRgba8(frame.into_buffer());
+            Ok(Frame {
+                pixels: convert_image(&image, options),
+                delay,
+            })
+        })
+        .collect()
+}
+
+/// Brightness of every pixel, with transparent pixels darkened accordingly
+fn luma_over_black(image: &DynamicImage) -> GrayImage {
+    let with_alpha = image.to_luma_alpha8();
+    GrayImage::from_fn(with_alpha.width(), with_alpha.height(), |x, y| {
+        let [luma, alpha] = with_alpha.get_pixel(x, y).0;
+        let value = u16::from(luma) * u16::from(alpha) / 255;
+        image::Luma([value as u8])
+    })
+}
+
+fn dither(image: &GrayImage, options: &ImageOptions) -> PixelGrid {
+    const BAYER: [[u8; 4]; 4] =
+        [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+    let (width, height) = (options.width, options.height);
+    let mut pixels = PixelGrid::new(width, height);
+    let value = |x: usize, y: usize| image.get_pixel(x as u32, y as u32).0[0];
+    match options.dithering {
+        Dithering::None => {
+            for y in 0..height {
+                for x in 0..width {
+                    pixels.set(x, y, value(x, y) > options.threshold);
+                }
+            }
+        }
+        Dithering::Ordered => {
+            for y in 0..height {
+                for x in 0..width {
+                    // thresholds spread evenly over 8..=248
+                    let threshold = BAYER[y % 4][x % 4] * 16 + 8;
+                    pixels.set(x, y, value(x, y) > threshold);
+                }
+            }
+        }
+        Dithering::FloydSteinberg => {
+            let mut errors = vec![0i16; width * height];
+            for y in 0..height {
+                for x in 0..width {
+                    let old = i16::from(value(x, y)) + errors[y * width + x];
+                    let on = old > 127;
+                    pixels.set(x, y, on);
+
+                    let error = old - if on { 255 } else { 0 };
+                    let mut spread = |dx: isize, dy: usize, weight: i16| {
+                        let Some(x) = x.checked_add_signed(dx) else {
+                            return;
+                        };
+                        if x < width && y + dy < height {
+                            errors[(y + dy) * width + x] += error * weight / 16;
+                        }
+                    };
+                    spread(1, 0, 7);
+                    spread(-1, 1, 3);
+                    spread(0, 1, 5);
+                    spread(1, 1, 1);
+                }
+            }
+        }
+    }
+    pixels
+}
+
+/// Plays image frames on the display, respecting the delay of each frame.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use servicepoint2::{load_frames, Connection, ImageOptions, ImagePlayer};
+///
+/// let connection = Connection::open("172.23.42.29:2342").unwrap();
+/// let frames = load_frames("logo.gif", &ImageOptions::default()).unwrap();
+///
+/// let mut player = ImagePlayer::new(frames);
+/// player.set_loops(Some(3));
+/// player.play(&connection).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ImagePlayer {
+    frames: Vec<Frame>,
+    origin: Origin,
+    compression: CompressionCode,
+    loops: Option<usize>,
+}
+
+impl ImagePlayer {
+    /// Creates a player showing the frames once at the top left of the screen.
+    #[must_use]
+    pub fn new(frames: Vec<Frame>) -> Self {
+        Self {
+            frames,
+            origin: Origin(0, 0),
+            compression: CompressionCode::Uncompressed,
+            loops: Some(1),
+        }
+    }
+
+    /// Sets where on screen the frames are shown.
+    /// The x-coordinate has to be dividable by 8.
+    pub fn set_origin(&mut self, origin: Origin) {
+        self.origin = origin;
+    }
+
+    /// Sets the compression used for sending frames.
+    pub fn set_compression(&mut self, compression: CompressionCode) {
+        self.compression = compression;
+    }
+
+    /// Sets how often the frames are played, `None` for playing forever.
+    pub fn set_loops(&mut self, loops: Option<usize>) {
+        self.loops = loops;
+    }
+
+    /// The frames that are played
+    #[must_use]
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    /// Sends the frames to the display, waiting the delay of each frame but at least
+    /// `FRAME_PACING` between them. When playing forever, this only returns on errors.
+    ///
+    /// # Errors
+    ///
+    /// Any errors produced while sending.
+    #[cfg(feature = "net")]
+    pub fn play(&self, connection: &Connection) -> std::io::Result<()> {
+        if self.frames.is_empty() {
+            return Ok(());
+        }
+
+        let mut remaining = self.loops;
+        while remaining != Some(0) {
+            for frame in &self.frames {
+                let command = Command::BitmapLinearWin(
+                    self.origin,
+                    frame.pixels.clone(),
+                    self.compression,
+                );
+                connection.send(command.into())?;
+                thread::sleep(frame.delay.max(FRAME_PACING));
+            }
+            remaining = remaining.map(|loops| loops - 1);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use image::codecs::gif::GifEncoder;
+    use image::{Delay, DynamicImage, GrayImage, Luma, Rgba, RgbaImage};
+
+    use crate::{
+        convert_image, decode_frames, Dithering, Grid, ImageOptions, Resize,
+    };
+
+    fn options(width: usize, height: usize) -> ImageOptions {
+        ImageOptions {
+            width,
+            height,
+            ..ImageOptions::default()
+        }
+    }
+
+    fn gray(width: u32, height: u32, value: u8) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_pixel(
+            width,
+            height,
+            Luma([value]),
+        ))
+    }
+
+    #[test]
+    fn threshold_and_invert() {
+        let mut image = GrayImage::new(8, 1);
+        image.put_pixel(2, 0, Luma([200]));
+        let image = DynamicImage::ImageLuma8(image);
+        let mut options = ImageOptions {
+            dithering: Dithering::None,
+            ..options(8, 1)
+        };
+
+        let pixels = convert_image(&image, &options);
+        assert!(pixels.get(2, 0));
+        assert_eq!(pixels.count_ones(), 1);
+
+        options.invert = true;
+        assert_eq!(convert_image(&image, &options).count_ones(), 7);
+    }
+
+    #[test]
+    fn fit_keeps_aspect_ratio() {
+        let white = gray(4, 4, 255);
+        let pixels = convert_image(&white, &options(16, 8));
+        assert!(!pixels.get(0, 4));
+        assert!(pixels.get(8, 4));
+        assert!(!pixels.get(15, 4));
+        assert_eq!(pixels.count_ones(), 64);
+
+        let stretched = ImageOptions {
+            resize: Resize::Stretch,
+            ..options(16, 8)
+        };
+        assert_eq!(convert_image(&white, &stretched).count_ones(), 128);
+    }
+
+    #[test]
+    fn transparent_is_black() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(
+            8,
+            1,
+            Rgba([255, 255, 255, 0]),
+        ));
+        assert_eq!(convert_image(&image, &options(8, 1)).count_ones(), 0);
+    }
+
+    #[test]
+    fn dithering_gray() {
+        let image = gray(32, 32, 128);
+        for dithering in [Dithering::Ordered, Dithering::FloydSteinberg] {
+            let options = ImageOptions {
+                dithering,
+                ..options(32, 32)
+            };
+            let on = convert_image(&image, &options).count_ones();
+            assert!((448..=576).contains(&on), "{dithering:?}: {on}");
+        }
+    }
+
+    #[test]
+    fn gif_frames_and_delays() {
+        let mut data = vec![];
+        {
+            let mut encoder = GifEncoder::new(&mut data);
+            for (value, delay) in [(0, 100), (255, 250)] {
+                let buffer = RgbaImage::from_pixel(
+                    8,
+                    2,
+                    Rgba([value, value, value, 255]),
+                );
+                let frame = image::Frame::from_parts(
+                    buffer,
+                    0,
+                    0,
+                    Delay::from_numer_denom_ms(delay, 1),
+                );
+                encoder.encode_frame(frame).unwrap();
+            }
+        }
+
+        let frames = decode_frames(&data, &options(8, 2)).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].delay, Duration::from_millis(100));
+        assert_eq!(frames[1].delay, Duration::from_millis(250));
+        assert_eq!(frames[0].pixels.count_ones(), 0);
+        assert_eq!(frames[1].pixels.count_ones(), 16);
+    }
+}
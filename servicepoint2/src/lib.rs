@@ -1,29 +1,69 @@
 use std::time::Duration;
 
+#[cfg(feature = "net")]
+pub use crate::animator::{run_loop, Animator, AnimatorHandle, FrameStats};
 pub use crate::bit_vec::BitVec;
+pub use crate::brightness::{Brightness, BrightnessMap, BrightnessOutOfRange};
 pub use crate::byte_grid::ByteGrid;
 #[cfg(feature = "c_api")]
 pub use crate::c_slice::CByteSlice;
-pub use crate::command::{Brightness, Command, Offset, Origin};
+pub use crate::canvas::Canvas;
+pub use crate::command::{Command, CommandKind, Offset, Origin};
 pub use crate::compression_code::CompressionCode;
+#[cfg(feature = "net")]
 pub use crate::connection::Connection;
+pub use crate::cp437::{char_to_cp437, cp437_to_char, text_to_cp437, NotCp437};
 pub use crate::data_ref::DataRef;
+pub use crate::font::{draw_text, text_width, GLYPH_ADVANCE, GLYPH_HEIGHT};
 pub use crate::grid::Grid;
-pub use crate::packet::{Header, Packet, Payload};
+pub use crate::header::{LinearHeader, TileHeader, WindowHeader};
+#[cfg(feature = "image")]
+pub use crate::images::{
+    convert_image, decode_frames, load_frames, load_image, Dithering, Frame,
+    ImageOptions, ImagePlayer, Resize,
+};
+pub use crate::packet::{Header, Packet, PacketRef, Payload};
 pub use crate::pixel_grid::PixelGrid;
+pub use crate::policy::{Area, InvalidPacketLimit, Policy, Rejection};
+pub use crate::recording::{Recorder, RecordingReader, RecordingWriter};
+pub use crate::scene::{BlendMode, Layer, Scene};
+pub use crate::screen_state::ScreenState;
+pub use crate::terminal::{render_pixels, TerminalPreview, TerminalStyle};
+pub use crate::ticker::Ticker;
+pub use crate::transition::{Direction, Transition, TransitionEffect};
 
+#[cfg(feature = "net")]
+mod animator;
 mod bit_vec;
+mod brightness;
 mod byte_grid;
 mod c_slice;
+mod canvas;
 mod command;
 mod command_code;
 mod compression;
 mod compression_code;
+#[cfg(feature = "net")]
 mod connection;
+mod cp437;
 mod data_ref;
+mod font;
 mod grid;
+mod header;
+#[cfg(feature = "image")]
+mod images;
 mod packet;
 mod pixel_grid;
+mod policy;
+mod random;
+mod recording;
+mod scene;
+mod screen_state;
+#[cfg(feature = "serde")]
+mod serialization;
+mod terminal;
+mod ticker;
+mod transition;
 
 /// size of a single tile in one dimension
 pub const TILE_SIZE: usize = 8;
@@ -1,7 +1,12 @@
+use std::fmt::{Display, Formatter};
 use std::mem::size_of;
 
+use crate::command_code::CommandCode;
+use crate::{LinearHeader, TileHeader, WindowHeader, TILE_SIZE};
+
 /// A raw header. Should probably not be used directly.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Header(pub u16, pub u16, pub u16, pub u16, pub u16);
 
 /// The raw payload. Should probably not be used directly.
@@ -11,20 +16,209 @@ pub type Payload = Vec<u8>;
 #[derive(Debug, PartialEq)]
 pub struct Packet(pub Header, pub Payload);
 
-impl From<Packet> for Vec<u8> {
-    /// Turn the packet into raw bytes ready to send
-    fn from(value: Packet) -> Self {
-        let Packet(Header(mode, a, b, c, d), payload) = value;
+/// A raw packet borrowing its payload from a byte slice.
+///
+/// This is the zero-copy counterpart of `Packet`, e.g. for inspecting received data without
+/// allocating.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PacketRef<'a>(pub Header, pub &'a [u8]);
+
+impl Header {
+    /// Writes the header to the first 10 bytes of the buffer
+    fn write_to(&self, buffer: &mut [u8]) {
+        let Header(mode, a, b, c, d) = *self;
+        buffer[0..=1].copy_from_slice(&u16::to_be_bytes(mode));
+        buffer[2..=3].copy_from_slice(&u16::to_be_bytes(a));
+        buffer[4..=5].copy_from_slice(&u16::to_be_bytes(b));
+        buffer[6..=7].copy_from_slice(&u16::to_be_bytes(c));
+        buffer[8..=9].copy_from_slice(&u16::to_be_bytes(d));
+    }
+}
+
+impl Packet {
+    /// Gets the size of the packet in bytes when serialized
+    #[must_use]
+    pub fn size(&self) -> usize {
+        self.as_ref().size()
+    }
+
+    /// Serializes the packet into the provided buffer without allocating.
+    ///
+    /// returns: the amount of bytes written
+    ///
+    /// # Panics
+    ///
+    /// When the buffer is smaller than `self.size()`
+    pub fn write_to(&self, buffer: &mut [u8]) -> usize {
+        self.as_ref().write_to(buffer)
+    }
+
+    /// Serializes the packet into the provided `Vec`, replacing its contents.
+    ///
+    /// The existing allocation is reused if it is big enough, so passing the same `Vec` for
+    /// every packet sent avoids allocating for each frame.
+    pub fn write_into(&self, buffer: &mut Vec<u8>) {
+        self.as_ref().write_into(buffer);
+    }
 
-        let mut packet = vec![0u8; 10 + payload.len()];
-        packet[0..=1].copy_from_slice(&u16::to_be_bytes(mode));
-        packet[2..=3].copy_from_slice(&u16::to_be_bytes(a));
-        packet[4..=5].copy_from_slice(&u16::to_be_bytes(b));
-        packet[6..=7].copy_from_slice(&u16::to_be_bytes(c));
-        packet[8..=9].copy_from_slice(&u16::to_be_bytes(d));
+    /// Borrows the packet as a `PacketRef`
+    #[must_use]
+    pub fn as_ref(&self) -> PacketRef<'_> {
+        let Packet(header, payload) = self;
+        PacketRef(*header, payload)
+    }
+}
 
-        packet[10..].copy_from_slice(&payload);
+impl PacketRef<'_> {
+    /// Gets the size of the packet in bytes when serialized
+    #[must_use]
+    pub fn size(&self) -> usize {
+        size_of::<Header>() + self.1.len()
+    }
 
+    /// Serializes the packet into the provided buffer without allocating.
+    ///
+    /// returns: the amount of bytes written
+    ///
+    /// # Panics
+    ///
+    /// When the buffer is smaller than `self.size()`
+    pub fn write_to(&self, buffer: &mut [u8]) -> usize {
+        let PacketRef(header, payload) = self;
+        let size = self.size();
+        assert!(
+            buffer.len() >= size,
+            "buffer of size {} is too small for packet of size {size}",
+            buffer.len()
+        );
+
+        header.write_to(&mut buffer[..size_of::<Header>()]);
+        buffer[size_of::<Header>()..size].copy_from_slice(payload);
+        size
+    }
+
+    /// Serializes the packet into the provided `Vec`, replacing its contents.
+    ///
+    /// The existing allocation is reused if it is big enough.
+    pub fn write_into(&self, buffer: &mut Vec<u8>) {
+        buffer.clear();
+        buffer.resize(self.size(), 0);
+        self.write_to(buffer);
+    }
+}
+
+impl Display for Packet {
+    /// Writes a one-line summary of the packet. See `PacketRef` for details.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.as_ref().fmt(f)
+    }
+}
+
+impl Display for PacketRef<'_> {
+    /// Writes a one-line summary of the packet, naming the header fields according to the
+    /// command code. For compressed payloads, the compression ratio is included.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let PacketRef(header, payload) = self;
+        let Header(code, a, b, c, d) = *header;
+        let command = CommandCode::try_from(code);
+        match command {
+            Ok(command) => write!(f, "{command:?} [0x{code:04x}]")?,
+            Err(()) => write!(f, "Unknown [0x{code:04x}]")?,
+        }
+
+        #[allow(deprecated)]
+        let uncompressed_size = match command {
+            Ok(
+                CommandCode::Clear
+                | CommandCode::Brightness
+                | CommandCode::HardReset
+                | CommandCode::FadeOut
+                | CommandCode::BitmapLegacy,
+            )
+            | Err(()) => {
+                write!(f, " header ({a}, {b}, {c}, {d})")?;
+                None
+            }
+            Ok(CommandCode::Cp437Data | CommandCode::CharBrightness) => {
+                let TileHeader {
+                    tile_x,
+                    tile_y,
+                    tile_w,
+                    tile_h,
+                    ..
+                } = (*header).into();
+                write!(
+                    f,
+                    " tile_x {tile_x} tile_y {tile_y} tile_w {tile_w} tile_h {tile_h}"
+                )?;
+                None
+            }
+            Ok(
+                CommandCode::BitmapLinear
+                | CommandCode::BitmapLinearAnd
+                | CommandCode::BitmapLinearOr
+                | CommandCode::BitmapLinearXor,
+            ) => {
+                let LinearHeader {
+                    offset,
+                    length,
+                    compression,
+                    reserved,
+                    ..
+                } = (*header).into();
+                write!(
+                    f,
+                    " offset {offset} length {length} compression 0x{compression:04x} reserved {reserved}"
+                )?;
+                (compression != 0).then_some(length as usize)
+            }
+            Ok(command) => {
+                // all remaining command codes are BitmapLinearWin variants
+                let WindowHeader {
+                    tile_x,
+                    pixel_y,
+                    tile_w,
+                    pixel_h,
+                    ..
+                } = (*header).into();
+                write!(
+                    f,
+                    " tile_x {tile_x} pixel_y {pixel_y} tile_w {tile_w} pixel_h {pixel_h}"
+                )?;
+                let size = tile_w as usize * TILE_SIZE * pixel_h as usize / 8;
+                let compressed = !matches!(
+                    command,
+                    CommandCode::BitmapLinearWinUncompressed
+                );
+                compressed.then_some(size)
+            }
+        };
+
+        write!(f, " payload {} bytes", payload.len())?;
+        match uncompressed_size {
+            Some(size) if size != 0 => write!(
+                f,
+                " (uncompressed {size} bytes, ratio {:.1}%)",
+                payload.len() as f64 * 100.0 / size as f64
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl From<PacketRef<'_>> for Packet {
+    /// Copies the borrowed payload into a new owned `Packet`
+    fn from(value: PacketRef<'_>) -> Self {
+        let PacketRef(header, payload) = value;
+        Packet(header, payload.to_vec())
+    }
+}
+
+impl From<Packet> for Vec<u8> {
+    /// Turn the packet into raw bytes ready to send
+    fn from(value: Packet) -> Self {
+        let mut packet = vec![];
+        value.write_into(&mut packet);
         packet
     }
 }
@@ -36,13 +230,13 @@ fn u16_from_be_slice(slice: &[u8]) -> u16 {
     u16::from_be_bytes(bytes)
 }
 
-impl TryFrom<&[u8]> for Packet {
+impl<'a> TryFrom<&'a [u8]> for PacketRef<'a> {
     type Error = ();
 
-    /// Tries to interpret the bytes as a `Packet`.
+    /// Tries to interpret the bytes as a `PacketRef` without copying the payload.
     ///
     /// returns: `Error` if slice is not long enough to be a `Packet`
-    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+    fn try_from(value: &'a [u8]) -> Result<Self, Self::Error> {
         if value.len() < size_of::<Header>() {
             return Err(());
         }
@@ -52,9 +246,20 @@ impl TryFrom<&[u8]> for Packet {
         let b = u16_from_be_slice(&value[4..=5]);
         let c = u16_from_be_slice(&value[6..=7]);
         let d = u16_from_be_slice(&value[8..=9]);
-        let payload = value[10..].to_vec();
+        let payload = &value[10..];
+
+        Ok(PacketRef(Header(mode, a, b, c, d), payload))
+    }
+}
 
-        Ok(Packet(Header(mode, a, b, c, d), payload))
+impl TryFrom<&[u8]> for Packet {
+    type Error = ();
+
+    /// Tries to interpret the bytes as a `Packet`.
+    ///
+    /// returns: `Error` if slice is not long enough to be a `Packet`
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        PacketRef::try_from(value).map(Packet::from)
     }
 }
 
@@ -62,7 +267,9 @@ impl TryFrom<&[u8]> for Packet {
 mod c_api {
     use std::ptr::null_mut;
 
-    use crate::{Command, Packet};
+    use crate::{
+        Command, Header, LinearHeader, Packet, TileHeader, WindowHeader,
+    };
 
     /// Turns a `Command` into a `Packet`. The command gets deallocated in the process.
     #[no_mangle]
@@ -89,6 +296,40 @@ mod c_api {
         }
     }
 
+    /// Gets the raw command code of the `Packet`.
+    #[no_mangle]
+    pub unsafe extern "C" fn sp2_packet_command_code(
+        this: *const Packet,
+    ) -> u16 {
+        let Packet(Header(command_code, ..), _) = &*this;
+        *command_code
+    }
+
+    /// Interprets the header of the `Packet` as the header of a `BitmapLinearWin` command.
+    #[no_mangle]
+    pub unsafe extern "C" fn sp2_packet_window_header(
+        this: *const Packet,
+    ) -> WindowHeader {
+        (*this).0.into()
+    }
+
+    /// Interprets the header of the `Packet` as the header of a `Cp437Data` or `CharBrightness`
+    /// command.
+    #[no_mangle]
+    pub unsafe extern "C" fn sp2_packet_tile_header(
+        this: *const Packet,
+    ) -> TileHeader {
+        (*this).0.into()
+    }
+
+    /// Interprets the header of the `Packet` as the header of a `BitmapLinear*` command.
+    #[no_mangle]
+    pub unsafe extern "C" fn sp2_packet_linear_header(
+        this: *const Packet,
+    ) -> LinearHeader {
+        (*this).0.into()
+    }
+
     /// Deallocates a `Packet`.
     ///
     /// Note: do not call this if the instance has been consumed in another way, e.g. by sending it.
@@ -100,7 +341,7 @@ mod c_api {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Header, Packet};
+    use crate::{Header, Packet, PacketRef};
 
     #[test]
     fn round_trip() {
@@ -109,4 +350,68 @@ mod tests {
         let p = Packet::try_from(&*data).unwrap();
         assert_eq!(p, Packet(Header(0, 1, 2, 3, 4), vec![42u8; 23]));
     }
+
+    #[test]
+    fn round_trip_ref() {
+        let p = Packet(Header(0, 1, 2, 3, 4), vec![42u8; 23]);
+        let mut buffer = [0u8; 64];
+        let size = p.write_to(&mut buffer);
+        assert_eq!(size, 33);
+
+        let r = PacketRef::try_from(&buffer[..size]).unwrap();
+        assert_eq!(r, p.as_ref());
+        assert_eq!(Packet::from(r), p);
+    }
+
+    #[test]
+    fn write_into_reuses() {
+        let mut buffer = vec![];
+        Packet(Header(0, 1, 2, 3, 4), vec![42u8; 23]).write_into(&mut buffer);
+        assert_eq!(buffer.len(), 33);
+
+        let capacity = buffer.capacity();
+        Packet(Header(5, 6, 7, 8, 9), vec![1u8; 2]).write_into(&mut buffer);
+        assert_eq!(buffer, [0, 5, 0, 6, 0, 7, 0, 8, 0, 9, 1, 1]);
+        assert_eq!(buffer.capacity(), capacity);
+    }
+
+    #[test]
+    fn too_short() {
+        assert_eq!(PacketRef::try_from(&[0u8; 9][..]), Err(()));
+        assert_eq!(Packet::try_from(&[0u8; 9][..]), Err(()));
+    }
+
+    #[test]
+    fn display() {
+        let p = Packet(Header(0x0002, 0, 0, 0, 0), vec![]);
+        assert_eq!(
+            p.to_string(),
+            "Clear [0x0002] header (0, 0, 0, 0) payload 0 bytes"
+        );
+
+        let p = Packet(Header(0x0013, 1, 2, 3, 4), vec![0; 12]);
+        assert_eq!(
+            p.to_string(),
+            "BitmapLinearWinUncompressed [0x0013] tile_x 1 pixel_y 2 tile_w 3 pixel_h 4 payload 12 bytes"
+        );
+
+        let p = Packet(Header(0x0012, 0, 40, 0x677a, 0), vec![0; 10]);
+        assert_eq!(
+            p.to_string(),
+            "BitmapLinear [0x0012] offset 0 length 40 compression 0x677a reserved 0 payload 10 bytes (uncompressed 40 bytes, ratio 25.0%)"
+        );
+
+        let p = Packet(Header(0x1234, 1, 2, 3, 4), vec![]);
+        assert_eq!(
+            p.to_string(),
+            "Unknown [0x1234] header (1, 2, 3, 4) payload 0 bytes"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn write_to_small_buffer() {
+        let p = Packet(Header(0, 1, 2, 3, 4), vec![42u8; 23]);
+        p.write_to(&mut [0u8; 32]);
+    }
 }
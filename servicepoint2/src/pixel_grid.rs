@@ -1,3 +1,5 @@
+use std::fmt::{Display, Formatter};
+
 use crate::{BitVec, DataRef, Grid, PIXEL_HEIGHT, PIXEL_WIDTH};
 
 /// A grid of pixels stored in packed bytes.
@@ -39,6 +41,27 @@ impl PixelGrid {
         }
     }
 
+    /// The number of pixels that are on.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use servicepoint2::{Grid, PixelGrid};
+    ///
+    /// let mut grid = PixelGrid::new(8, 2);
+    /// grid.set(3, 1, true);
+    /// assert_eq!(grid.count_ones(), 1);
+    /// ```
+    #[must_use]
+    pub fn count_ones(&self) -> usize {
+        // the width is a multiple of 8, so there are no padding bits
+        self.bit_vec
+            .data_ref()
+            .iter()
+            .map(|byte| byte.count_ones() as usize)
+            .sum()
+    }
+
     fn check_indexes(&self, x: usize, y: usize) {
         assert!(
             x < self.width,
@@ -120,6 +143,21 @@ impl DataRef for PixelGrid {
     }
 }
 
+impl Display for PixelGrid {
+    /// Renders the pixels as rows of `█` (on) and ` ` (off).
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            if y != 0 {
+                writeln!(f)?;
+            }
+            for x in 0..self.width {
+                write!(f, "{}", if self.get(x, y) { '█' } else { ' ' })?;
+            }
+        }
+        Ok(())
+    }
+}
+
 impl From<PixelGrid> for Vec<u8> {
     /// Turns a `PixelGrid` into the underlying `Vec<u8>`.
     fn from(value: PixelGrid) -> Self {
@@ -250,9 +288,11 @@ mod tests {
 
         grid.fill(true);
         assert_eq!(grid.data_ref(), [0xFF, 0xFF]);
+        assert_eq!(grid.count_ones(), 16);
 
         grid.fill(false);
         assert_eq!(grid.data_ref(), [0x00, 0x00]);
+        assert_eq!(grid.count_ones(), 0);
     }
 
     #[test]
@@ -286,4 +326,10 @@ mod tests {
         let grid = PixelGrid::load(8, 3, &data);
         assert_eq!(grid.data_ref(), [0xAA, 0x55, 0xAA]);
     }
+
+    #[test]
+    fn display() {
+        let grid = PixelGrid::load(8, 2, &[0xAA, 0x0F]);
+        assert_eq!(grid.to_string(), "█ █ █ █ \n    ████");
+    }
 }
@@ -0,0 +1,590 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::hash::Hash;
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+use crate::{
+    Brightness, Command, CommandKind, Grid, Origin, PIXEL_WIDTH, TILE_HEIGHT,
+    TILE_SIZE, TILE_WIDTH,
+};
+
+/// A rectangular part of the screen, in tiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Area {
+    /// column of the top left tile
+    pub x: usize,
+    /// row of the top left tile
+    pub y: usize,
+    /// size in tiles in x-direction
+    pub width: usize,
+    /// size in tiles in y-direction
+    pub height: usize,
+}
+
+impl Area {
+    /// The whole screen
+    pub const FULL_SCREEN: Area = Area {
+        x: 0,
+        y: 0,
+        width: TILE_WIDTH,
+        height: TILE_HEIGHT,
+    };
+
+    /// Checks whether the other area lies completely within this one.
+    #[must_use]
+    pub fn contains(&self, other: &Area) -> bool {
+        other.x >= self.x
+            && other.y >= self.y
+            && other.x + other.width <= self.x + self.width
+            && other.y + other.height <= self.y + self.height
+    }
+
+    /// The tiles a command writes to.
+    ///
+    /// Commands affecting the whole display, e.g. `Command::Clear`, cover the whole screen.
+    /// Pixels that only partly cover a tile count as the whole tile.
+    #[must_use]
+    pub fn of_command(command: &Command) -> Area {
+        match command {
+            Command::CharBrightness(Origin(x, y), grid)
+            | Command::Cp437Data(Origin(x, y), grid) => Area {
+                x: *x,
+                y: *y,
+                width: grid.width(),
+                height: grid.height(),
+            },
+            Command::BitmapLinearWin(Origin(x, y), pixels, _) => {
+                let end_y = y + pixels.height();
+                Area {
+                    x: x / TILE_SIZE,
+                    y: y / TILE_SIZE,
+                    width: pixels.width().div_ceil(TILE_SIZE),
+                    height: end_y.div_ceil(TILE_SIZE) - y / TILE_SIZE,
+                }
+            }
+            Command::BitmapLinear(offset, bits, _)
+            | Command::BitmapLinearAnd(offset, bits, _)
+            | Command::BitmapLinearOr(offset, bits, _)
+            | Command::BitmapLinearXor(offset, bits, _) => {
+                if bits.is_empty() {
+                    return Area {
+                        x: 0,
+                        y: 0,
+                        width: 0,
+                        height: 0,
+                    };
+                }
+                let (first, last) = (*offset, offset + bits.len() - 1);
+                let (first_y, last_y) =
+                    (first / PIXEL_WIDTH, last / PIXEL_WIDTH);
+                let (x, width) = if first_y == last_y {
+                    let first_x = first % PIXEL_WIDTH / TILE_SIZE;
+                    let last_x = last % PIXEL_WIDTH / TILE_SIZE;
+                    (first_x, last_x - first_x + 1)
+                } else {
+                    (0, TILE_WIDTH)
+                };
+                Area {
+                    x,
+                    y: first_y / TILE_SIZE,
+                    width,
+                    height: last_y / TILE_SIZE - first_y / TILE_SIZE + 1,
+                }
+            }
+            Command::Clear
+            | Command::HardReset
+            | Command::FadeOut
+            | Command::Brightness(_) => Area::FULL_SCREEN,
+            #[allow(deprecated)]
+            Command::BitmapLegacy => Area::FULL_SCREEN,
+        }
+    }
+}
+
+/// The reason a `Policy` rejected a command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rejection {
+    /// this kind of command is not allowed at all
+    Denied(CommandKind),
+    /// the command writes to tiles outside the allowed areas
+    OutsideAllowedArea(CommandKind, Area),
+    /// the source sent too many packets, try again after the duration
+    TooManyPackets(Duration),
+    /// the brightness was changed too recently, try again after the duration
+    BrightnessChangedTooOften(Duration),
+}
+
+impl Display for Rejection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rejection::Denied(kind) => write!(f, "{kind:?} is not allowed"),
+            Rejection::OutsideAllowedArea(kind, area) => write!(
+                f,
+                "{kind:?} writes to tiles ({}, {}) size {}x{} outside of the allowed areas",
+                area.x, area.y, area.width, area.height
+            ),
+            Rejection::TooManyPackets(retry_after) => write!(
+                f,
+                "too many packets, retry after {}ms",
+                retry_after.as_millis()
+            ),
+            Rejection::BrightnessChangedTooOften(retry_after) => write!(
+                f,
+                "brightness changed too often, retry after {}ms",
+                retry_after.as_millis()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Rejection {}
+
+/// A packet limit for `Policy::max_packets_per_second` that is not a positive, finite number.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InvalidPacketLimit(pub f64);
+
+impl Display for InvalidPacketLimit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "packet limit has to be a positive number, got {}",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for InvalidPacketLimit {}
+
+/// What a `Policy` remembers about a source
+#[derive(Debug)]
+struct SourceState {
+    /// token bucket for the packet rate
+    tokens: f64,
+    last_refill: Instant,
+    last_brightness_change: Option<Instant>,
+}
+
+/// Rules for which commands may be sent to the display, checked before sending or when
+/// forwarding commands from untrusted sources.
+///
+/// Limits are tracked per source, which can be anything identifying the sender, e.g. a
+/// `SocketAddr` in a proxy or `()` if there is only one.
+///
+/// By default, everything is allowed.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "net", doc = "```rust")]
+#[cfg_attr(not(feature = "net"), doc = "```rust,ignore")]
+/// use servicepoint2::{
+///     Brightness, Command, CommandKind, Connection, Policy, Rejection,
+/// };
+///
+/// let dimmed = Brightness::saturating_from(8);
+/// let mut policy = Policy::new()
+///     .deny(CommandKind::HardReset)
+///     .brightness_range(Brightness::MIN..=dimmed)
+///     .max_packets_per_second(20.0)
+///     .unwrap();
+///
+/// assert_eq!(
+///     policy.check((), Command::HardReset),
+///     Err(Rejection::Denied(CommandKind::HardReset))
+/// );
+/// assert_eq!(
+///     policy.check((), Command::Brightness(Brightness::MAX)),
+///     Ok(Command::Brightness(dimmed))
+/// );
+///
+/// let connection = Connection::open("172.23.42.29:2342").unwrap();
+/// if let Ok(command) = policy.check((), Command::Clear) {
+///     connection.send(command.into()).unwrap();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Policy<S = ()> {
+    denied: Vec<CommandKind>,
+    allowed_areas: Option<Vec<Area>>,
+    brightness_range: Option<RangeInclusive<Brightness>>,
+    min_brightness_interval: Option<Duration>,
+    max_packets_per_second: Option<f64>,
+    sources: HashMap<S, SourceState>,
+}
+
+impl<S: Eq + Hash> Policy<S> {
+    /// Creates a policy that allows everything.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            denied: vec![],
+            allowed_areas: None,
+            brightness_range: None,
+            min_brightness_interval: None,
+            max_packets_per_second: None,
+            sources: HashMap::new(),
+        }
+    }
+
+    /// Rejects all commands of the specified kind.
+    #[must_use]
+    pub fn deny(mut self, kind: CommandKind) -> Self {
+        self.denied.push(kind);
+        self
+    }
+
+    /// Only allows commands that write within one of the areas.
+    ///
+    /// Calling this multiple times allows all of the specified areas. Commands affecting the
+    /// whole display, like `Command::Clear`, are only allowed if the whole screen is allowed.
+    #[must_use]
+    pub fn allow_area(mut self, area: Area) -> Self {
+        self.allowed_areas.get_or_insert_with(Vec::new).push(area);
+        self
+    }
+
+    /// Clamps brightness values of `Command::Brightness` and `Command::CharBrightness`
+    /// into the range instead of rejecting them.
+    #[must_use]
+    pub fn brightness_range(
+        mut self,
+        range: RangeInclusive<Brightness>,
+    ) -> Self {
+        self.brightness_range = Some(range);
+        self
+    }
+
+    /// Rejects brightness changes of a source that follow its last brightness change
+    /// within the interval.
+    #[must_use]
+    pub fn min_brightness_interval(mut self, interval: Duration) -> Self {
+        self.min_brightness_interval = Some(interval);
+        self
+    }
+
+    /// Limits the packets per second of each source.
+    ///
+    /// Short bursts of up to one second worth of packets are allowed, but at least one packet.
+    ///
+    /// # Errors
+    ///
+    /// - `InvalidPacketLimit` when the limit is not a positive, finite number
+    pub fn max_packets_per_second(
+        mut self,
+        limit: f64,
+    ) -> Result<Self, InvalidPacketLimit> {
+        if !limit.is_finite() || limit <= 0.0 {
+            return Err(InvalidPacketLimit(limit));
+        }
+        self.max_packets_per_second = Some(limit);
+        Ok(self)
+    }
+
+    /// Checks a command from the source against the rules at the current time.
+    ///
+    /// returns: the command to send, which may be changed to match the rules, or the reason it
+    /// was rejected.
+    ///
+    /// # Errors
+    ///
+    /// The `Rejection` if the command is not allowed.
+    pub fn check(
+        &mut self,
+        source: S,
+        command: Command,
+    ) -> Result<Command, Rejection> {
+        self.check_at(source, command, Instant::now())
+    }
+
+    /// Checks a command from the source against the rules, pretending the current time is
+    /// `now`.
+    ///
+    /// Every checked command counts towards the packet limit, even if it gets rejected.
+    ///
+    /// # Errors
+    ///
+    /// The `Rejection` if the command is not allowed.
+    pub fn check_at(
+        &mut self,
+        source: S,
+        command: Command,
+        now: Instant,
+    ) -> Result<Command, Rejection> {
+        let max_packets_per_second = self.max_packets_per_second;
+        // with less than one packet per second, a full bucket still holds one packet
+        let capacity = max_packets_per_second.map(|limit| limit.max(1.0));
+        let state = self.sources.entry(source).or_insert_with(|| SourceState {
+            tokens: capacity.unwrap_or_default(),
+            last_refill: now,
+            last_brightness_change: None,
+        });
+
+        if let (Some(limit), Some(capacity)) =
+            (max_packets_per_second, capacity)
+        {
+            let elapsed = now.saturating_duration_since(state.last_refill);
+            state.tokens =
+                (state.tokens + elapsed.as_secs_f64() * limit).min(capacity);
+            state.last_refill = now;
+            if state.tokens < 1.0 {
+                let missing = 1.0 - state.tokens;
+                return Err(Rejection::TooManyPackets(
+                    Duration::from_secs_f64(missing / limit),
+                ));
+            }
+            state.tokens -= 1.0;
+        }
+
+        let kind = command.kind();
+        if self.denied.contains(&kind) {
+            return Err(Rejection::Denied(kind));
+        }
+
+        if let Some(allowed_areas) = &self.allowed_areas {
+            let area = Area::of_command(&command);
+            if !allowed_areas.iter().any(|allowed| allowed.contains(&area)) {
+                return Err(Rejection::OutsideAllowedArea(kind, area));
+            }
+        }
+
+        let changes_brightness = matches!(
+            kind,
+            CommandKind::Brightness | CommandKind::CharBrightness
+        );
+        if !changes_brightness {
+            return Ok(command);
+        }
+
+        if let (Some(interval), Some(last)) =
+            (self.min_brightness_interval, state.last_brightness_change)
+        {
+            let elapsed = now.saturating_duration_since(last);
+            if elapsed < interval {
+                return Err(Rejection::BrightnessChangedTooOften(
+                    interval - elapsed,
+                ));
+            }
+        }
+        state.last_brightness_change = Some(now);
+
+        Ok(match (command, &self.brightness_range) {
+            (Command::Brightness(value), Some(range)) => {
+                Command::Brightness(clamp(value, range))
+            }
+            (Command::CharBrightness(origin, mut grid), Some(range)) => {
+                for y in 0..grid.height() {
+                    for x in 0..grid.width() {
+                        let value = Brightness::saturating_from(grid.get(x, y));
+                        grid.set(x, y, clamp(value, range).into());
+                    }
+                }
+                Command::CharBrightness(origin, grid)
+            }
+            (command, _) => command,
+        })
+    }
+
+    /// Drops everything remembered about the source, e.g. when it disconnects.
+    pub fn forget(&mut self, source: &S) {
+        self.sources.remove(source);
+    }
+}
+
+impl<S: Eq + Hash> Default for Policy<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn clamp(value: Brightness, range: &RangeInclusive<Brightness>) -> Brightness {
+    value.clamp(*range.start(), *range.end())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::{
+        Area, BitVec, Brightness, ByteGrid, Command, CommandKind,
+        CompressionCode, Grid, Origin, PixelGrid, Policy, Rejection,
+        PIXEL_WIDTH,
+    };
+
+    fn brightness(value: u8) -> Command {
+        Command::Brightness(Brightness::saturating_from(value))
+    }
+
+    #[test]
+    fn allows_everything_by_default() {
+        let mut policy = Policy::new();
+        assert_eq!(
+            policy.check((), Command::HardReset),
+            Ok(Command::HardReset)
+        );
+    }
+
+    #[test]
+    fn deny() {
+        let mut policy = Policy::new().deny(CommandKind::HardReset);
+        assert_eq!(
+            policy.check((), Command::HardReset),
+            Err(Rejection::Denied(CommandKind::HardReset))
+        );
+        assert_eq!(policy.check((), Command::Clear), Ok(Command::Clear));
+    }
+
+    #[test]
+    fn clamp_brightness() {
+        let mut policy = Policy::new().brightness_range(
+            Brightness::saturating_from(2)..=Brightness::saturating_from(8),
+        );
+        assert_eq!(policy.check((), brightness(11)), Ok(brightness(8)));
+        assert_eq!(
+            policy.check(
+                (),
+                Command::CharBrightness(
+                    Origin(0, 0),
+                    ByteGrid::load(3, 1, &[0, 5, 42])
+                )
+            ),
+            Ok(Command::CharBrightness(
+                Origin(0, 0),
+                ByteGrid::load(3, 1, &[2, 5, 8])
+            ))
+        );
+    }
+
+    #[test]
+    fn brightness_interval() {
+        let mut policy =
+            Policy::new().min_brightness_interval(Duration::from_secs(1));
+        let start = Instant::now();
+
+        assert!(policy.check_at(1, brightness(5), start).is_ok());
+        assert_eq!(
+            policy.check_at(
+                1,
+                brightness(6),
+                start + Duration::from_millis(400)
+            ),
+            Err(Rejection::BrightnessChangedTooOften(Duration::from_millis(
+                600
+            )))
+        );
+        assert!(policy.check_at(2, brightness(6), start).is_ok());
+        assert!(policy
+            .check_at(1, Command::Clear, start + Duration::from_millis(500))
+            .is_ok());
+        assert!(policy
+            .check_at(1, brightness(6), start + Duration::from_secs(1))
+            .is_ok());
+    }
+
+    #[test]
+    fn packet_rate() {
+        let mut policy = Policy::new().max_packets_per_second(2.0).unwrap();
+        let start = Instant::now();
+
+        assert!(policy.check_at(1, Command::Clear, start).is_ok());
+        assert!(policy.check_at(1, Command::Clear, start).is_ok());
+        assert_eq!(
+            policy.check_at(1, Command::Clear, start),
+            Err(Rejection::TooManyPackets(Duration::from_millis(500)))
+        );
+        assert!(policy.check_at(2, Command::Clear, start).is_ok());
+        assert!(policy
+            .check_at(1, Command::Clear, start + Duration::from_millis(500))
+            .is_ok());
+    }
+
+    #[test]
+    fn slow_packet_rate() {
+        let mut policy = Policy::new().max_packets_per_second(0.5).unwrap();
+        let start = Instant::now();
+
+        assert!(policy.check_at((), Command::Clear, start).is_ok());
+        assert_eq!(
+            policy.check_at((), Command::Clear, start),
+            Err(Rejection::TooManyPackets(Duration::from_secs(2)))
+        );
+        let later = start + Duration::from_secs(2);
+        assert!(policy.check_at((), Command::Clear, later).is_ok());
+        assert!(policy.check_at((), Command::Clear, later).is_err());
+    }
+
+    #[test]
+    fn invalid_packet_rate() {
+        for limit in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let result = Policy::<()>::new().max_packets_per_second(limit);
+            assert!(result.is_err(), "{limit}");
+        }
+    }
+
+    #[test]
+    fn allowed_areas() {
+        let area = Area {
+            x: 2,
+            y: 1,
+            width: 4,
+            height: 2,
+        };
+        let mut policy = Policy::new().allow_area(area);
+
+        let inside = Command::Cp437Data(Origin(2, 1), ByteGrid::new(4, 2));
+        assert_eq!(policy.check((), inside.clone()), Ok(inside));
+
+        let outside = Command::Cp437Data(Origin(3, 1), ByteGrid::new(4, 2));
+        assert!(matches!(
+            policy.check((), outside),
+            Err(Rejection::OutsideAllowedArea(CommandKind::Cp437Data, _))
+        ));
+
+        let pixels = Command::BitmapLinearWin(
+            Origin(16, 9),
+            PixelGrid::new(32, 7),
+            CompressionCode::Uncompressed,
+        );
+        assert!(policy.check((), pixels).is_ok());
+
+        assert_eq!(
+            policy.check((), Command::Clear),
+            Err(Rejection::OutsideAllowedArea(
+                CommandKind::Clear,
+                Area::FULL_SCREEN
+            ))
+        );
+    }
+
+    #[test]
+    fn area_of_linear() {
+        let command = Command::BitmapLinear(
+            PIXEL_WIDTH * 8 + 16,
+            BitVec::new(16),
+            CompressionCode::Uncompressed,
+        );
+        assert_eq!(
+            Area::of_command(&command),
+            Area {
+                x: 2,
+                y: 1,
+                width: 2,
+                height: 1
+            }
+        );
+
+        let command = Command::BitmapLinearXor(
+            PIXEL_WIDTH * 7,
+            BitVec::new(PIXEL_WIDTH * 2),
+            CompressionCode::Uncompressed,
+        );
+        assert_eq!(
+            Area::of_command(&command),
+            Area {
+                x: 0,
+                y: 0,
+                width: Area::FULL_SCREEN.width,
+                height: 2
+            }
+        );
+    }
+}
@@ -0,0 +1,48 @@
+const SEED_MIX: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// A small deterministic random number generator (xorshift64*).
+///
+/// Good enough for visual effects, the same seed always results in the same numbers.
+#[derive(Debug, Clone)]
+pub(crate) struct XorShift(u64);
+
+impl XorShift {
+    pub(crate) fn new(seed: u64) -> Self {
+        // the state must not be zero, or all numbers are zero
+        let state = seed ^ SEED_MIX;
+        Self(if state == 0 { SEED_MIX } else { state })
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// A number in `0..bound`
+    pub(crate) fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{XorShift, SEED_MIX};
+
+    #[test]
+    fn deterministic() {
+        let mut a = XorShift::new(42);
+        let mut b = XorShift::new(42);
+        for _ in 0..10 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn seed_resulting_in_zero_state() {
+        let mut random = XorShift::new(SEED_MIX);
+        assert_ne!(random.next_u64(), 0);
+        assert_ne!(random.next_u64(), random.next_u64());
+    }
+}
@@ -0,0 +1,344 @@
+use std::io::{ErrorKind, Read, Write};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "net")]
+use crate::Connection;
+use crate::Packet;
+
+const MAGIC: &[u8; 6] = b"SP2REC";
+const VERSION: u16 = 1;
+/// Packets are sent as single UDP datagrams, so they cannot be any larger
+const MAX_PACKET_SIZE: usize = u16::MAX as usize;
+
+/// Writes packets with timestamps in the recording format.
+///
+/// # File format
+///
+/// A recording starts with the 6 byte magic `SP2REC` followed by the format version as a
+/// big-endian `u16` (currently `1`). After that, packets follow until the end of the file, each
+/// encoded as:
+///
+/// | size    | content                                                    |
+/// |---------|------------------------------------------------------------|
+/// | 8 bytes | big-endian `u64`, microseconds since the recording started |
+/// | 4 bytes | big-endian `u32`, length of the packet in bytes            |
+/// | n bytes | the packet, exactly as sent over UDP                       |
+pub struct RecordingWriter<W: Write> {
+    writer: W,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> RecordingWriter<W> {
+    /// Starts a new recording by writing the file header.
+    ///
+    /// # Errors
+    ///
+    /// Any errors of the underlying writer.
+    pub fn new(mut writer: W) -> std::io::Result<Self> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_be_bytes())?;
+        Ok(Self {
+            writer,
+            buffer: vec![],
+        })
+    }
+
+    /// Appends a packet to the recording.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp`: time since the start of the recording
+    /// * `packet`: the packet to record
+    ///
+    /// # Errors
+    ///
+    /// Any errors of the underlying writer.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn write(
+        &mut self,
+        timestamp: Duration,
+        packet: &Packet,
+    ) -> std::io::Result<()> {
+        packet.write_into(&mut self.buffer);
+        self.writer
+            .write_all(&(timestamp.as_micros() as u64).to_be_bytes())?;
+        self.writer
+            .write_all(&(self.buffer.len() as u32).to_be_bytes())?;
+        self.writer.write_all(&self.buffer)
+    }
+
+    /// Flushes and returns the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// Any errors while flushing.
+    pub fn finish(mut self) -> std::io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Reads packets with timestamps from a recording in the format described in
+/// `RecordingWriter`.
+///
+/// Iterating yields the packets in recorded order together with the time since the start of
+/// the recording.
+pub struct RecordingReader<R: Read> {
+    reader: R,
+}
+
+impl<R: Read> RecordingReader<R> {
+    /// Opens a recording by checking the file header.
+    ///
+    /// # Errors
+    ///
+    /// - `ErrorKind::InvalidData` if the data is not a recording in a supported version
+    /// - Any errors of the underlying reader
+    pub fn new(mut reader: R) -> std::io::Result<Self> {
+        let mut header = [0u8; 8];
+        reader.read_exact(&mut header)?;
+        if &header[0..6] != MAGIC {
+            return Err(std::io::Error::new(
+                ErrorKind::InvalidData,
+                "not a servicepoint recording",
+            ));
+        }
+
+        let version = u16::from_be_bytes([header[6], header[7]]);
+        if version != VERSION {
+            return Err(std::io::Error::new(
+                ErrorKind::InvalidData,
+                format!("unsupported recording version {version}"),
+            ));
+        }
+
+        Ok(Self { reader })
+    }
+
+    fn read_record(&mut self) -> std::io::Result<Option<(Duration, Packet)>> {
+        // only the end of the data before a record is a clean end
+        let mut timestamp = [0u8; 8];
+        let first = loop {
+            match self.reader.read(&mut timestamp[..1]) {
+                Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                result => break result?,
+            }
+        };
+        if first == 0 {
+            return Ok(None);
+        }
+        self.reader.read_exact(&mut timestamp[1..]).map_err(|err| {
+            if err.kind() == ErrorKind::UnexpectedEof {
+                std::io::Error::new(
+                    ErrorKind::InvalidData,
+                    "recording ends inside a timestamp",
+                )
+            } else {
+                err
+            }
+        })?;
+
+        let mut length = [0u8; 4];
+        self.reader.read_exact(&mut length)?;
+        let length = u32::from_be_bytes(length) as usize;
+        if length > MAX_PACKET_SIZE {
+            return Err(std::io::Error::new(
+                ErrorKind::InvalidData,
+                format!("record of {length} bytes is too big for a packet"),
+            ));
+        }
+        let mut data = vec![0u8; length];
+        self.reader.read_exact(&mut data)?;
+
+        let packet = Packet::try_from(&*data).map_err(|()| {
+            std::io::Error::new(ErrorKind::InvalidData, "invalid packet")
+        })?;
+        let timestamp = Duration::from_micros(u64::from_be_bytes(timestamp));
+        Ok(Some((timestamp, packet)))
+    }
+}
+
+impl<R: Read> Iterator for RecordingReader<R> {
+    type Item = std::io::Result<(Duration, Packet)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+/// Records all packets sent through it, optionally forwarding them to a `Connection`.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "net", doc = "```rust")]
+#[cfg_attr(not(feature = "net"), doc = "```rust,ignore")]
+/// use servicepoint2::{Command, Connection, Recorder, RecordingWriter};
+///
+/// let connection = Connection::open("172.23.42.29:2342").unwrap();
+/// let file = std::io::Cursor::new(vec![]);
+/// let mut recorder =
+///     Recorder::new(connection, RecordingWriter::new(file).unwrap());
+///
+/// recorder.send(Command::Clear.into()).unwrap();
+/// ```
+pub struct Recorder<W: Write> {
+    #[cfg(feature = "net")]
+    connection: Option<Connection>,
+    writer: RecordingWriter<W>,
+    start: Instant,
+}
+
+impl<W: Write> Recorder<W> {
+    /// Creates a recorder that sends all packets to the connection.
+    ///
+    /// Timestamps are relative to the time this is called.
+    #[cfg(feature = "net")]
+    pub fn new(connection: Connection, writer: RecordingWriter<W>) -> Self {
+        Self {
+            connection: Some(connection),
+            writer,
+            start: Instant::now(),
+        }
+    }
+
+    /// Creates a recorder that does not send anything, e.g. for preparing a recording offline.
+    ///
+    /// Timestamps are relative to the time this is called.
+    pub fn offline(writer: RecordingWriter<W>) -> Self {
+        Self {
+            #[cfg(feature = "net")]
+            connection: None,
+            writer,
+            start: Instant::now(),
+        }
+    }
+
+    /// Records the packet and sends it if there is a connection.
+    ///
+    /// # Errors
+    ///
+    /// Any errors produced while writing the recording or sending.
+    pub fn send(&mut self, packet: Packet) -> std::io::Result<()> {
+        self.writer.write(self.start.elapsed(), &packet)?;
+        #[cfg(feature = "net")]
+        if let Some(connection) = &self.connection {
+            return connection.send(packet);
+        }
+        Ok(())
+    }
+
+    /// Ends the recording.
+    ///
+    /// returns: the underlying writer
+    ///
+    /// # Errors
+    ///
+    /// Any errors while flushing the recording.
+    pub fn finish(self) -> std::io::Result<W> {
+        self.writer.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind};
+    use std::time::Duration;
+
+    use crate::{
+        Command, Header, Packet, Recorder, RecordingReader, RecordingWriter,
+    };
+
+    #[test]
+    fn round_trip() {
+        let mut writer = RecordingWriter::new(vec![]).unwrap();
+        writer
+            .write(Duration::from_millis(5), &Command::Clear.into())
+            .unwrap();
+        writer
+            .write(
+                Duration::from_secs(2),
+                &Packet(Header(1, 2, 3, 4, 5), vec![6, 7]),
+            )
+            .unwrap();
+        let data = writer.finish().unwrap();
+
+        let records: Vec<_> = RecordingReader::new(Cursor::new(data))
+            .unwrap()
+            .collect::<std::io::Result<_>>()
+            .unwrap();
+        assert_eq!(
+            records,
+            vec![
+                (Duration::from_millis(5), Command::Clear.into()),
+                (
+                    Duration::from_secs(2),
+                    Packet(Header(1, 2, 3, 4, 5), vec![6, 7])
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn offline_recorder() {
+        let mut recorder =
+            Recorder::offline(RecordingWriter::new(vec![]).unwrap());
+        recorder.send(Command::Clear.into()).unwrap();
+        recorder.send(Command::FadeOut.into()).unwrap();
+        let data = recorder.finish().unwrap();
+
+        let packets: Vec<Packet> = RecordingReader::new(Cursor::new(data))
+            .unwrap()
+            .map(|record| record.unwrap().1)
+            .collect();
+        assert_eq!(
+            packets,
+            vec![Command::Clear.into(), Command::FadeOut.into()]
+        );
+    }
+
+    #[test]
+    fn invalid_magic() {
+        let result = RecordingReader::new(Cursor::new(b"NOTAREC\x01"));
+        assert_eq!(result.err().unwrap().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn oversized_record() {
+        let mut data = RecordingWriter::new(vec![]).unwrap().finish().unwrap();
+        data.extend_from_slice(&[0; 8]);
+        data.extend_from_slice(&u32::MAX.to_be_bytes());
+
+        let mut reader = RecordingReader::new(Cursor::new(data)).unwrap();
+        let err = reader.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn truncated_record() {
+        let mut writer = RecordingWriter::new(vec![]).unwrap();
+        writer
+            .write(Duration::ZERO, &Command::Clear.into())
+            .unwrap();
+        let mut data = writer.finish().unwrap();
+        data.pop();
+
+        let mut reader = RecordingReader::new(Cursor::new(data)).unwrap();
+        let err = reader.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn truncated_timestamp() {
+        let mut writer = RecordingWriter::new(vec![]).unwrap();
+        writer
+            .write(Duration::ZERO, &Command::Clear.into())
+            .unwrap();
+        let mut data = writer.finish().unwrap();
+        data.extend_from_slice(&[0; 3]);
+
+        let mut reader = RecordingReader::new(Cursor::new(data)).unwrap();
+        assert!(reader.next().unwrap().is_ok());
+        let err = reader.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
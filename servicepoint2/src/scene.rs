@@ -0,0 +1,322 @@
+use crate::{Command, CompressionCode, Grid, Origin, PixelGrid};
+
+/// How the pixels of a `Layer` are combined with the layers below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlendMode {
+    /// the layer replaces the pixels below
+    #[default]
+    Over,
+    /// pixels that are on in the layer toggle the pixels below
+    Xor,
+    /// only pixels that are on in both the layer and below stay on
+    And,
+    /// the layer replaces the pixels below with its pixels inverted
+    Invert,
+}
+
+/// A movable image in a `Scene`.
+///
+/// All fields can be changed between frames, e.g. for animating the position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layer {
+    /// the contents of the layer
+    pub pixels: PixelGrid,
+    /// position of the left edge in the scene, may be outside of the scene
+    pub x: isize,
+    /// position of the top edge in the scene, may be outside of the scene
+    pub y: isize,
+    /// hidden layers are skipped when rendering
+    pub visible: bool,
+    /// only pixels that are on in the mask are drawn. Pixels outside of the mask count as off.
+    /// Without a mask, the whole layer is drawn.
+    pub mask: Option<PixelGrid>,
+    /// how the layer is combined with the layers below
+    pub blend: BlendMode,
+}
+
+impl Layer {
+    /// Creates a visible layer at (0, 0) without a mask using `BlendMode::Over`.
+    #[must_use]
+    pub fn new(pixels: PixelGrid) -> Self {
+        Self {
+            pixels,
+            x: 0,
+            y: 0,
+            visible: true,
+            mask: None,
+            blend: BlendMode::Over,
+        }
+    }
+
+    /// Creates a layer that only draws the pixels that are on, leaving the rest transparent.
+    #[must_use]
+    pub fn transparent(pixels: PixelGrid) -> Self {
+        Self {
+            mask: Some(pixels.clone()),
+            ..Self::new(pixels)
+        }
+    }
+
+    /// Moves the layer by the specified amount of pixels.
+    pub fn move_by(&mut self, dx: isize, dy: isize) {
+        self.x += dx;
+        self.y += dy;
+    }
+
+    /// Whether the pixel at the position in the layer is drawn
+    fn is_opaque(&self, x: usize, y: usize) -> bool {
+        match &self.mask {
+            None => true,
+            Some(mask) => {
+                x < mask.width() && y < mask.height() && mask.get(x, y)
+            }
+        }
+    }
+}
+
+/// A stack of layers composited into a single frame.
+///
+/// The first layer is at the bottom, later layers are drawn on top of it.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "net", doc = "```rust")]
+#[cfg_attr(not(feature = "net"), doc = "```rust,ignore")]
+/// use servicepoint2::{
+///     BlendMode, CompressionCode, Connection, Grid, Layer, Origin, PixelGrid, Scene,
+/// };
+///
+/// let connection = Connection::open("172.23.42.29:2342").unwrap();
+/// let mut background = PixelGrid::new(64, 16);
+/// background.fill(true);
+///
+/// let mut scene = Scene::new(64, 16);
+/// scene.push(Layer::new(background));
+/// let overlay = scene.push(Layer {
+///     blend: BlendMode::Invert,
+///     ..Layer::new(PixelGrid::new(16, 8))
+/// });
+///
+/// for _ in 0..3 {
+///     scene.layer_mut(overlay).move_by(8, 0);
+///     let command = scene.command(Origin(0, 0), CompressionCode::Uncompressed);
+///     connection.send(command.into()).unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scene {
+    width: usize,
+    height: usize,
+    layers: Vec<Layer>,
+}
+
+impl Scene {
+    /// Creates an empty scene of the specified size.
+    ///
+    /// # Panics
+    ///
+    /// - when the width is not dividable by 8
+    #[must_use]
+    pub fn new(width: usize, height: usize) -> Self {
+        assert_eq!(width % 8, 0);
+        Self {
+            width,
+            height,
+            layers: vec![],
+        }
+    }
+
+    /// Adds a layer on top of all others.
+    ///
+    /// returns: the index of the layer
+    pub fn push(&mut self, layer: Layer) -> usize {
+        self.layers.push(layer);
+        self.layers.len() - 1
+    }
+
+    /// Get the layer at the index
+    ///
+    /// # Panics
+    ///
+    /// - when there is no layer at the index
+    #[must_use]
+    pub fn layer(&self, index: usize) -> &Layer {
+        &self.layers[index]
+    }
+
+    /// Get the layer at the index for changing it
+    ///
+    /// # Panics
+    ///
+    /// - when there is no layer at the index
+    pub fn layer_mut(&mut self, index: usize) -> &mut Layer {
+        &mut self.layers[index]
+    }
+
+    /// All layers from bottom to top. Changing the order changes the z-order.
+    pub fn layers_mut(&mut self) -> &mut Vec<Layer> {
+        &mut self.layers
+    }
+
+    /// the size in x-direction
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// the size in y-direction
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Composites all visible layers into a new frame.
+    #[must_use]
+    pub fn render(&self) -> PixelGrid {
+        let mut frame = PixelGrid::new(self.width, self.height);
+        self.render_into(&mut frame);
+        frame
+    }
+
+    /// Composites all visible layers into an existing frame, starting with all pixels off.
+    ///
+    /// Parts of the frame outside of the scene are left unchanged.
+    pub fn render_into(&self, frame: &mut PixelGrid) {
+        let width = self.width.min(frame.width());
+        let height = self.height.min(frame.height());
+        for y in 0..height {
+            for x in 0..width {
+                frame.set(x, y, false);
+            }
+        }
+
+        for layer in self.layers.iter().filter(|layer| layer.visible) {
+            for layer_y in 0..layer.pixels.height() {
+                let Some(y) = offset(layer.y, layer_y, height) else {
+                    continue;
+                };
+                for layer_x in 0..layer.pixels.width() {
+                    let Some(x) = offset(layer.x, layer_x, width) else {
+                        continue;
+                    };
+                    if !layer.is_opaque(layer_x, layer_y) {
+                        continue;
+                    }
+
+                    let below = frame.get(x, y);
+                    let pixel = layer.pixels.get(layer_x, layer_y);
+                    let value = match layer.blend {
+                        BlendMode::Over => pixel,
+                        BlendMode::Xor => below ^ pixel,
+                        BlendMode::And => below && pixel,
+                        BlendMode::Invert => !pixel,
+                    };
+                    frame.set(x, y, value);
+                }
+            }
+        }
+    }
+
+    /// Renders the scene into a command for showing it at the origin.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin`: position of the top left pixel on screen. The x-coordinate has to be
+    ///   dividable by 8.
+    /// * `compression`: the compression to use for sending
+    #[must_use]
+    pub fn command(
+        &self,
+        origin: Origin,
+        compression: CompressionCode,
+    ) -> Command {
+        Command::BitmapLinearWin(origin, self.render(), compression)
+    }
+}
+
+/// The position in the scene of a pixel in a layer, if it is inside of the scene
+fn offset(position: isize, index: usize, size: usize) -> Option<usize> {
+    let result = usize::try_from(position.checked_add_unsigned(index)?).ok()?;
+    (result < size).then_some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BlendMode, Grid, Layer, PixelGrid, Scene};
+
+    fn filled(width: usize, height: usize) -> PixelGrid {
+        let mut pixels = PixelGrid::new(width, height);
+        pixels.fill(true);
+        pixels
+    }
+
+    #[test]
+    fn over_is_clipped() {
+        let mut scene = Scene::new(16, 4);
+        let index = scene.push(Layer::new(filled(8, 2)));
+        scene.layer_mut(index).x = -4;
+        scene.layer_mut(index).y = 3;
+
+        let frame = scene.render();
+        assert!(frame.get(3, 3));
+        assert!(!frame.get(4, 3));
+        assert!(!frame.get(0, 2));
+    }
+
+    #[test]
+    fn z_order_and_visibility() {
+        let mut scene = Scene::new(8, 1);
+        scene.push(Layer::new(filled(8, 1)));
+        let top = scene.push(Layer::new(PixelGrid::new(8, 1)));
+        assert!(!scene.render().get(0, 0));
+
+        scene.layer_mut(top).visible = false;
+        assert!(scene.render().get(0, 0));
+
+        scene.layer_mut(top).visible = true;
+        scene.layers_mut().swap(0, 1);
+        assert!(scene.render().get(0, 0));
+    }
+
+    #[test]
+    fn mask() {
+        let mut overlay = PixelGrid::new(8, 1);
+        overlay.set(1, 0, true);
+
+        let mut scene = Scene::new(8, 1);
+        let background = scene.push(Layer::new(PixelGrid::new(8, 1)));
+        scene.layer_mut(background).pixels.set(5, 0, true);
+        scene.push(Layer::transparent(overlay));
+
+        let frame = scene.render();
+        assert!(frame.get(1, 0));
+        assert!(frame.get(5, 0));
+        assert!(!frame.get(0, 0));
+    }
+
+    #[test]
+    fn blend_modes() {
+        let mut pattern = PixelGrid::new(8, 1);
+        pattern.set(0, 0, true);
+        pattern.set(1, 0, true);
+        let mut layer = PixelGrid::new(8, 1);
+        layer.set(1, 0, true);
+        layer.set(2, 0, true);
+
+        let render = |blend| {
+            let mut scene = Scene::new(8, 1);
+            scene.push(Layer::new(pattern.clone()));
+            scene.push(Layer {
+                blend,
+                ..Layer::new(layer.clone())
+            });
+            let frame = scene.render();
+            (0..4).map(|x| frame.get(x, 0)).collect::<Vec<_>>()
+        };
+
+        assert_eq!(render(BlendMode::Over), vec![false, true, true, false]);
+        assert_eq!(render(BlendMode::Xor), vec![true, false, true, false]);
+        assert_eq!(render(BlendMode::And), vec![false, true, false, false]);
+        assert_eq!(render(BlendMode::Invert), vec![true, false, false, true]);
+    }
+}
@@ -0,0 +1,232 @@
+use crate::{
+    BitVec, Brightness, ByteGrid, Command, Grid, Origin, PixelGrid,
+    PIXEL_COUNT, TILE_HEIGHT, TILE_WIDTH,
+};
+
+/// A model of what the display currently shows, built by applying the `Command`s sent to it.
+///
+/// This is useful for simulators, previews and for inspecting recorded traffic.
+///
+/// The display renders `Cp437Data` into its pixel buffer using a built-in font. As the font is
+/// not available here, the text is tracked in a separate layer instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use servicepoint2::{Command, CompressionCode, Grid, Origin, PixelGrid, ScreenState};
+///
+/// let mut pixels = PixelGrid::new(8, 1);
+/// pixels.fill(true);
+///
+/// let mut state = ScreenState::new();
+/// state.apply(&Command::BitmapLinearWin(Origin(8, 2), pixels, CompressionCode::Uncompressed));
+/// assert!(state.pixels().get(15, 2));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScreenState {
+    pixels: PixelGrid,
+    chars: ByteGrid,
+    brightness: ByteGrid,
+}
+
+impl ScreenState {
+    /// Creates the state of a freshly reset display: all pixels off, no text and maximum
+    /// brightness.
+    #[must_use]
+    pub fn new() -> Self {
+        let mut brightness = ByteGrid::new(TILE_WIDTH, TILE_HEIGHT);
+        brightness.fill(Brightness::default().into());
+        Self {
+            pixels: PixelGrid::max_sized(),
+            chars: ByteGrid::new(TILE_WIDTH, TILE_HEIGHT),
+            brightness,
+        }
+    }
+
+    /// Updates the state the same way the display would when receiving the command.
+    ///
+    /// Parts of the command outside the screen are ignored.
+    pub fn apply(&mut self, command: &Command) {
+        match command {
+            Command::Clear => {
+                self.pixels.fill(false);
+                self.chars.fill(0);
+            }
+            Command::HardReset => *self = Self::new(),
+            // the exact behavior is unknown, and the display recovers on its own
+            Command::FadeOut => {}
+            #[allow(deprecated)]
+            Command::BitmapLegacy => {}
+            Command::Brightness(brightness) => {
+                self.brightness.fill((*brightness).into())
+            }
+            Command::CharBrightness(origin, grid) => {
+                copy_clipped(&mut self.brightness, *origin, grid);
+            }
+            Command::Cp437Data(origin, grid) => {
+                copy_clipped(&mut self.chars, *origin, grid);
+            }
+            Command::BitmapLinear(offset, bits, _) => {
+                self.apply_linear(*offset, bits, |_, new| new);
+            }
+            Command::BitmapLinearAnd(offset, bits, _) => {
+                self.apply_linear(*offset, bits, |old, new| old && new);
+            }
+            Command::BitmapLinearOr(offset, bits, _) => {
+                self.apply_linear(*offset, bits, |old, new| old || new);
+            }
+            Command::BitmapLinearXor(offset, bits, _) => {
+                self.apply_linear(*offset, bits, |old, new| old ^ new);
+            }
+            Command::BitmapLinearWin(Origin(x, y), pixels, _) => {
+                let w =
+                    pixels.width().min(self.pixels.width().saturating_sub(*x));
+                let h = pixels
+                    .height()
+                    .min(self.pixels.height().saturating_sub(*y));
+                for win_y in 0..h {
+                    for win_x in 0..w {
+                        let value = pixels.get(win_x, win_y);
+                        self.pixels.set(x + win_x, y + win_y, value);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The pixels currently shown, in the size of the whole screen
+    #[must_use]
+    pub fn pixels(&self) -> &PixelGrid {
+        &self.pixels
+    }
+
+    /// The CP437 encoded text currently shown, one byte per tile
+    #[must_use]
+    pub fn chars(&self) -> &ByteGrid {
+        &self.chars
+    }
+
+    /// The current brightness, one byte per tile
+    #[must_use]
+    pub fn brightness(&self) -> &ByteGrid {
+        &self.brightness
+    }
+
+    /// Combines the bits with the pixels starting at the pixel offset, row by row.
+    fn apply_linear(
+        &mut self,
+        offset: usize,
+        bits: &BitVec,
+        op: impl Fn(bool, bool) -> bool,
+    ) {
+        let width = self.pixels.width();
+        let count = bits.len().min(PIXEL_COUNT.saturating_sub(offset));
+        for index in 0..count {
+            let (x, y) = ((offset + index) % width, (offset + index) / width);
+            let value = op(self.pixels.get(x, y), bits.get(index));
+            self.pixels.set(x, y, value);
+        }
+    }
+}
+
+impl Default for ScreenState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Copies the source grid to the origin of the target, ignoring cells outside the target.
+fn copy_clipped(target: &mut ByteGrid, origin: Origin, source: &ByteGrid) {
+    let Origin(x, y) = origin;
+    let w = source.width().min(target.width().saturating_sub(x));
+    let h = source.height().min(target.height().saturating_sub(y));
+    for src_y in 0..h {
+        for src_x in 0..w {
+            target.set(x + src_x, y + src_y, source.get(src_x, src_y));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        BitVec, Brightness, ByteGrid, Command, CompressionCode, Grid, Origin,
+        PixelGrid, ScreenState, PIXEL_WIDTH,
+    };
+
+    #[test]
+    fn bitmap_win_clipped() {
+        let mut pixels = PixelGrid::new(16, 2);
+        pixels.fill(true);
+
+        let mut state = ScreenState::new();
+        state.apply(&Command::BitmapLinearWin(
+            Origin(PIXEL_WIDTH - 8, 5),
+            pixels,
+            CompressionCode::Uncompressed,
+        ));
+
+        assert!(state.pixels().get(PIXEL_WIDTH - 8, 5));
+        assert!(state.pixels().get(PIXEL_WIDTH - 1, 6));
+        assert!(!state.pixels().get(PIXEL_WIDTH - 9, 5));
+        assert!(!state.pixels().get(0, 6));
+    }
+
+    #[test]
+    fn bitmap_linear_ops() {
+        let mut state = ScreenState::new();
+        let mut bits = BitVec::new(16);
+        bits.fill(true);
+
+        state.apply(&Command::BitmapLinear(
+            PIXEL_WIDTH - 8,
+            bits.clone(),
+            CompressionCode::Uncompressed,
+        ));
+        assert!(state.pixels().get(PIXEL_WIDTH - 8, 0));
+        assert!(state.pixels().get(7, 1));
+        assert!(!state.pixels().get(8, 1));
+
+        bits.set(0, false);
+        state.apply(&Command::BitmapLinearAnd(
+            PIXEL_WIDTH - 8,
+            bits.clone(),
+            CompressionCode::Uncompressed,
+        ));
+        assert!(!state.pixels().get(PIXEL_WIDTH - 8, 0));
+
+        state.apply(&Command::BitmapLinearXor(
+            PIXEL_WIDTH - 8,
+            bits,
+            CompressionCode::Uncompressed,
+        ));
+        assert!(!state.pixels().get(PIXEL_WIDTH - 7, 0));
+        assert!(!state.pixels().get(7, 1));
+    }
+
+    #[test]
+    fn text_and_brightness() {
+        let mut state = ScreenState::new();
+        state.apply(&Command::Cp437Data(
+            Origin(1, 2),
+            ByteGrid::load(2, 1, b"hi"),
+        ));
+        assert_eq!(state.chars().get(1, 2), b'h');
+        assert_eq!(state.chars().get(2, 2), b'i');
+
+        state.apply(&Command::Brightness(Brightness::saturating_from(3)));
+        state.apply(&Command::CharBrightness(
+            Origin(0, 0),
+            ByteGrid::load(1, 1, &[7]),
+        ));
+        assert_eq!(state.brightness().get(0, 0), 7);
+        assert_eq!(state.brightness().get(1, 0), 3);
+
+        state.apply(&Command::Clear);
+        assert_eq!(state.chars().get(1, 2), 0);
+        assert_eq!(state.brightness().get(1, 0), 3);
+
+        state.apply(&Command::HardReset);
+        assert_eq!(state, ScreenState::new());
+    }
+}
@@ -0,0 +1,509 @@
+//! `Serialize` and `Deserialize` implementations for types that need a custom representation.
+//!
+//! Grids and raw data are stored as packed bytes in binary formats. In human-readable formats
+//! like JSON, pixels are written as rows of `#` and `.` and bytes as rows of numbers instead.
+
+use std::fmt::Formatter;
+
+use serde::de::{Error, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    BitVec, Brightness, BrightnessOutOfRange, ByteGrid, DataRef, Grid, Header,
+    Packet, PixelGrid,
+};
+
+/// Raw bytes, serialized as bytes instead of a sequence of numbers where possible
+struct Bytes(Vec<u8>);
+
+impl Serialize for Bytes {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bytes {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        struct BytesVisitor;
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = Bytes;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                f.write_str("bytes")
+            }
+
+            fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Bytes, E> {
+                Ok(Bytes(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Bytes, E> {
+                Ok(Bytes(v))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<Bytes, A::Error> {
+                let mut bytes =
+                    Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                Ok(Bytes(bytes))
+            }
+        }
+
+        deserializer.deserialize_bytes(BytesVisitor)
+    }
+}
+
+/// The representation of grids: the size and either packed bytes or human-readable rows
+#[derive(Serialize, Deserialize)]
+struct GridData<T> {
+    width: usize,
+    height: usize,
+    data: T,
+}
+
+impl Serialize for Brightness {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8((*self).into())
+    }
+}
+
+impl<'de> Deserialize<'de> for Brightness {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let value = u8::deserialize(deserializer)?;
+        Brightness::new(value)
+            .ok_or_else(|| D::Error::custom(BrightnessOutOfRange(value)))
+    }
+}
+
+impl Serialize for BitVec {
+    /// A string of `0` and `1` when human-readable, packed bytes otherwise
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            let bits: String = (0..self.len())
+                .map(|index| if self.get(index) { '1' } else { '0' })
+                .collect();
+            serializer.serialize_str(&bits)
+        } else {
+            serializer.serialize_bytes(self.data_ref())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BitVec {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        if !deserializer.is_human_readable() {
+            let Bytes(data) = Bytes::deserialize(deserializer)?;
+            return Ok(BitVec::from(data.as_slice()));
+        }
+
+        let bits = String::deserialize(deserializer)?;
+        if bits.len() % 8 != 0 {
+            return Err(D::Error::custom(format!(
+                "bit count {} is not dividable by 8",
+                bits.len()
+            )));
+        }
+        let mut result = BitVec::new(bits.len());
+        for (index, bit) in bits.chars().enumerate() {
+            match bit {
+                '0' => {}
+                '1' => _ = result.set(index, true),
+                other => {
+                    return Err(D::Error::custom(format!(
+                        "unexpected bit '{other}', expected '0' or '1'"
+                    )))
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl Serialize for PixelGrid {
+    /// Rows of `#` (on) and `.` (off) when human-readable, packed bytes otherwise
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let (width, height) = (self.width(), self.height());
+        if !serializer.is_human_readable() {
+            let data = Bytes(self.data_ref().to_vec());
+            return GridData {
+                width,
+                height,
+                data,
+            }
+            .serialize(serializer);
+        }
+
+        let data: Vec<String> = (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| if self.get(x, y) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+        GridData {
+            width,
+            height,
+            data,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PixelGrid {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        if !deserializer.is_human_readable() {
+            let GridData {
+                width,
+                height,
+                data: Bytes(data),
+            } = GridData::deserialize(deserializer)?;
+            let size = width.checked_mul(height).map(|pixels| pixels / 8);
+            if width % 8 != 0 || size != Some(data.len()) {
+                return Err(D::Error::custom(format!(
+                    "{} bytes do not match a pixel grid of {width}x{height}",
+                    data.len()
+                )));
+            }
+            return Ok(PixelGrid::load(width, height, &data));
+        }
+
+        let GridData {
+            width,
+            height,
+            data,
+        } = GridData::<Vec<String>>::deserialize(deserializer)?;
+        if width % 8 != 0 {
+            return Err(D::Error::custom(format!(
+                "width {width} is not dividable by 8"
+            )));
+        }
+        check_row_count(data.len(), height)?;
+        // before allocating, so the size is limited by the actual data
+        for (y, row) in data.iter().enumerate() {
+            check_row_length(row.chars().count(), width, y)?;
+        }
+
+        let mut result = PixelGrid::new(width, height);
+        for (y, row) in data.iter().enumerate() {
+            for (x, pixel) in row.chars().enumerate() {
+                match pixel {
+                    '.' | ' ' => {}
+                    '#' => _ = result.set(x, y, true),
+                    other => {
+                        return Err(D::Error::custom(format!(
+                            "unexpected pixel '{other}', expected '#' or '.'"
+                        )))
+                    }
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl Serialize for ByteGrid {
+    /// Rows of numbers when human-readable, packed bytes otherwise
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let (width, height) = (self.width(), self.height());
+        if !serializer.is_human_readable() {
+            let data = Bytes(self.data_ref().to_vec());
+            return GridData {
+                width,
+                height,
+                data,
+            }
+            .serialize(serializer);
+        }
+
+        let data: Vec<Vec<u8>> = (0..height)
+            .map(|y| (0..width).map(|x| self.get(x, y)).collect())
+            .collect();
+        GridData {
+            width,
+            height,
+            data,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteGrid {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        if !deserializer.is_human_readable() {
+            let GridData {
+                width,
+                height,
+                data: Bytes(data),
+            } = GridData::deserialize(deserializer)?;
+            if width.checked_mul(height) != Some(data.len()) {
+                return Err(D::Error::custom(format!(
+                    "{} bytes do not match a byte grid of {width}x{height}",
+                    data.len()
+                )));
+            }
+            return Ok(ByteGrid::load(width, height, &data));
+        }
+
+        let GridData {
+            width,
+            height,
+            data,
+        } = GridData::<Vec<Vec<u8>>>::deserialize(deserializer)?;
+        check_row_count(data.len(), height)?;
+        for (y, row) in data.iter().enumerate() {
+            check_row_length(row.len(), width, y)?;
+        }
+        Ok(ByteGrid::load(width, height, &data.concat()))
+    }
+}
+
+fn check_row_count<E: Error>(rows: usize, height: usize) -> Result<(), E> {
+    if rows == height {
+        Ok(())
+    } else {
+        Err(E::custom(format!("expected {height} rows, found {rows}")))
+    }
+}
+
+fn check_row_length<E: Error>(
+    length: usize,
+    width: usize,
+    y: usize,
+) -> Result<(), E> {
+    if length == width {
+        Ok(())
+    } else {
+        Err(E::custom(format!(
+            "expected row {y} to be {width} long, found {length}"
+        )))
+    }
+}
+
+/// The representation of packets, with the payload as packed bytes
+#[derive(Serialize, Deserialize)]
+struct PacketData {
+    header: Header,
+    payload: Bytes,
+}
+
+impl Serialize for Packet {
+    /// The header and the payload, which is a list of numbers when human-readable and packed
+    /// bytes otherwise
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let Packet(header, payload) = self;
+        PacketData {
+            header: *header,
+            payload: Bytes(payload.clone()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Packet {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let PacketData {
+            header,
+            payload: Bytes(payload),
+        } = PacketData::deserialize(deserializer)?;
+        Ok(Packet(header, payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_test::{
+        assert_de_tokens_error, assert_tokens, Compact, Configure, Token,
+    };
+
+    use crate::{
+        BitVec, Brightness, ByteGrid, Command, CompressionCode, Grid, Header,
+        Origin, Packet, PixelGrid,
+    };
+
+    fn grid_tokens(name: &'static str, data: Token) -> Vec<Token> {
+        vec![
+            Token::Struct { name, len: 3 },
+            Token::Str("width"),
+            Token::U64(8),
+            Token::Str("height"),
+            Token::U64(2),
+            Token::Str("data"),
+            data,
+            Token::StructEnd,
+        ]
+    }
+
+    #[test]
+    fn pixel_grid() {
+        let mut pixels = PixelGrid::new(8, 2);
+        pixels.set(0, 0, true);
+        pixels.set(7, 1, true);
+
+        assert_tokens(
+            &pixels.clone().compact(),
+            &grid_tokens("GridData", Token::Bytes(&[0x80, 0x01])),
+        );
+
+        let mut readable = grid_tokens("GridData", Token::Seq { len: Some(2) });
+        readable.splice(
+            7..7,
+            [
+                Token::Str("#......."),
+                Token::Str(".......#"),
+                Token::SeqEnd,
+            ],
+        );
+        assert_tokens(&pixels.readable(), &readable);
+    }
+
+    #[test]
+    fn byte_grid() {
+        const DATA: [u8; 16] = [1, 2, 3, 4, 5, 6, 7, 8, 0, 0, 0, 0, 0, 0, 0, 9];
+        let grid = ByteGrid::load(8, 2, &DATA);
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(
+            json,
+            r#"{"width":8,"height":2,"data":[[1,2,3,4,5,6,7,8],[0,0,0,0,0,0,0,9]]}"#
+        );
+        assert_eq!(serde_json::from_str::<ByteGrid>(&json).unwrap(), grid);
+
+        assert_tokens(
+            &grid.compact(),
+            &grid_tokens("GridData", Token::Bytes(&DATA)),
+        );
+    }
+
+    #[test]
+    fn bit_vec() {
+        let mut bits = BitVec::new(8);
+        bits.set(1, true);
+        assert_tokens(&bits.clone().readable(), &[Token::Str("01000000")]);
+        assert_tokens(&bits.compact(), &[Token::Bytes(&[0x40])]);
+    }
+
+    #[test]
+    fn brightness_is_validated() {
+        assert_tokens(&Brightness::MAX, &[Token::U8(11)]);
+        assert_de_tokens_error::<Brightness>(
+            &[Token::U8(12)],
+            "brightness 12 is higher than the maximum of 11",
+        );
+    }
+
+    #[test]
+    fn invalid_grids() {
+        let error = serde_json::from_str::<PixelGrid>(
+            r##"{"width":8,"height":1,"data":["#..x...."]}"##,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("unexpected pixel 'x'"));
+
+        let error = serde_json::from_str::<ByteGrid>(
+            r#"{"width":2,"height":2,"data":[[1,2]]}"#,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("expected 2 rows, found 1"));
+    }
+
+    #[test]
+    fn huge_grids() {
+        let error = serde_json::from_str::<PixelGrid>(
+            r##"{"width":1000000000000000,"height":1,"data":["#"]}"##,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("expected row 0 to be"));
+
+        let error = serde_json::from_str::<ByteGrid>(
+            r#"{"width":1000000000000000,"height":2,"data":[[1],[2]]}"#,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("expected row 0 to be"));
+
+        let mut tokens = grid_tokens("GridData", Token::Bytes(&[0, 0]));
+        tokens[2] = Token::U64(u64::MAX - 7);
+        tokens[4] = Token::U64(16);
+        assert_de_tokens_error::<Compact<PixelGrid>>(
+            &tokens,
+            &format!(
+                "2 bytes do not match a pixel grid of {}x16",
+                u64::MAX - 7
+            ),
+        );
+        tokens[2] = Token::U64(u64::MAX);
+        assert_de_tokens_error::<Compact<ByteGrid>>(
+            &tokens,
+            &format!("2 bytes do not match a byte grid of {}x16", u64::MAX),
+        );
+    }
+
+    #[test]
+    fn packet() {
+        let packet = Packet(Header(1, 2, 3, 4, 5), vec![6, 7]);
+        let json = serde_json::to_string(&packet).unwrap();
+        assert_eq!(json, r#"{"header":[1,2,3,4,5],"payload":[6,7]}"#);
+        assert_eq!(serde_json::from_str::<Packet>(&json).unwrap(), packet);
+    }
+
+    #[test]
+    fn commands_round_trip() {
+        let mut pixels = PixelGrid::new(16, 2);
+        pixels.set(3, 1, true);
+        let commands = vec![
+            Command::Clear,
+            Command::Brightness(Brightness::MIN),
+            Command::Cp437Data(Origin(1, 2), ByteGrid::load(2, 1, b"hi")),
+            Command::BitmapLinearWin(
+                Origin(8, 0),
+                pixels,
+                CompressionCode::Uncompressed,
+            ),
+            Command::BitmapLinearXor(
+                16,
+                BitVec::new(8),
+                CompressionCode::Uncompressed,
+            ),
+        ];
+
+        let json = serde_json::to_string(&commands).unwrap();
+        assert!(json.contains(r#"{"Cp437Data":[[1,2],"#));
+        assert_eq!(
+            serde_json::from_str::<Vec<Command>>(&json).unwrap(),
+            commands
+        );
+    }
+}
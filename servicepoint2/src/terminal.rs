@@ -0,0 +1,273 @@
+use std::io::{Stdout, Write};
+#[cfg(feature = "net")]
+use std::sync::{Mutex, PoisonError};
+
+use log::warn;
+
+#[cfg(feature = "net")]
+use crate::Connection;
+use crate::{Command, Grid, Packet, PixelGrid, ScreenState};
+
+/// How pixels are drawn as characters in a terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TerminalStyle {
+    /// one character per 1x2 pixels using half blocks like '▀'.
+    /// The screen is 448x80 characters.
+    #[default]
+    HalfBlocks,
+    /// one character per 2x4 pixels using Braille patterns like '⣿'.
+    /// The screen is 224x40 characters.
+    Braille,
+}
+
+/// Draws pixels as lines of text for showing them in a terminal.
+///
+/// # Examples
+///
+/// ```rust
+/// use servicepoint2::{render_pixels, Grid, PixelGrid, TerminalStyle};
+///
+/// let mut pixels = PixelGrid::new(8, 2);
+/// pixels.set(0, 0, true);
+/// pixels.set(1, 1, true);
+/// assert_eq!(render_pixels(&pixels, TerminalStyle::HalfBlocks), "▀▄      ");
+/// ```
+#[must_use]
+pub fn render_pixels(pixels: &PixelGrid, style: TerminalStyle) -> String {
+    let (cell_width, cell_height) = match style {
+        TerminalStyle::HalfBlocks => (1, 2),
+        TerminalStyle::Braille => (2, 4),
+    };
+    // pixels outside of the grid count as off
+    let get = |x: usize, y: usize| {
+        x < pixels.width() && y < pixels.height() && pixels.get(x, y)
+    };
+
+    let mut lines = vec![];
+    for row in 0..pixels.height().div_ceil(cell_height) {
+        let mut line = String::new();
+        for column in 0..pixels.width().div_ceil(cell_width) {
+            let (x, y) = (column * cell_width, row * cell_height);
+            line.push(match style {
+                TerminalStyle::HalfBlocks => match (get(x, y), get(x, y + 1)) {
+                    (false, false) => ' ',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (true, true) => '█',
+                },
+                TerminalStyle::Braille => braille(|dx, dy| get(x + dx, y + dy)),
+            });
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+/// The Braille pattern for a cell of 2x4 pixels
+fn braille(get: impl Fn(usize, usize) -> bool) -> char {
+    // bit of each dot in the unicode block, by position
+    const DOTS: [(usize, usize, u32); 8] = [
+        (0, 0, 0x01),
+        (0, 1, 0x02),
+        (0, 2, 0x04),
+        (1, 0, 0x08),
+        (1, 1, 0x10),
+        (1, 2, 0x20),
+        (0, 3, 0x40),
+        (1, 3, 0x80),
+    ];
+    let bits = DOTS
+        .iter()
+        .filter(|(dx, dy, _)| get(*dx, *dy))
+        .fold(0, |bits, (_, _, bit)| bits | bit);
+    char::from_u32(0x2800 + bits).unwrap_or(' ')
+}
+
+/// A live view of what the display shows, redrawn in the terminal after every command.
+///
+/// The text layer is only drawn below the pixels once it contains something. It is decoded
+/// from CP437 like the `Display` implementation of `ByteGrid` does.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "net", doc = "```rust")]
+#[cfg_attr(not(feature = "net"), doc = "```rust,ignore")]
+/// use servicepoint2::{Command, Connection, TerminalPreview};
+///
+/// let mut connection = Connection::open("172.23.42.29:2342").unwrap();
+/// TerminalPreview::new(std::io::sink()).attach(&mut connection);
+///
+/// // also drawn in the terminal
+/// connection.send(Command::Clear.into()).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct TerminalPreview<W: Write = Stdout> {
+    writer: W,
+    state: ScreenState,
+    style: TerminalStyle,
+}
+
+impl TerminalPreview<Stdout> {
+    /// Creates a preview drawing to stdout.
+    #[must_use]
+    pub fn stdout() -> Self {
+        Self::new(std::io::stdout())
+    }
+}
+
+impl<W: Write> TerminalPreview<W> {
+    /// Creates a preview of an empty screen drawing to the writer using half blocks.
+    #[must_use]
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            state: ScreenState::new(),
+            style: TerminalStyle::HalfBlocks,
+        }
+    }
+
+    /// Sets how pixels are drawn.
+    pub fn set_style(&mut self, style: TerminalStyle) {
+        self.style = style;
+    }
+
+    /// What the display shows according to the commands so far
+    #[must_use]
+    pub fn state(&self) -> &ScreenState {
+        &self.state
+    }
+
+    /// Applies the command and redraws the preview.
+    ///
+    /// # Errors
+    ///
+    /// Any errors produced while writing.
+    pub fn show(&mut self, command: &Command) -> std::io::Result<()> {
+        self.state.apply(command);
+        self.draw()
+    }
+
+    /// Applies the command contained in the packet and redraws the preview.
+    /// Packets that are not valid commands are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Any errors produced while writing.
+    pub fn show_packet(&mut self, packet: &Packet) -> std::io::Result<()> {
+        let Packet(header, payload) = packet;
+        match Command::try_from(Packet(*header, payload.clone())) {
+            Ok(command) => self.show(&command),
+            Err(err) => {
+                warn!("not showing invalid packet in preview: {err:?}");
+                Ok(())
+            }
+        }
+    }
+
+    /// Draws the current state over the previous one.
+    ///
+    /// # Errors
+    ///
+    /// Any errors produced while writing.
+    pub fn draw(&mut self) -> std::io::Result<()> {
+        // move the cursor to the top left instead of clearing to avoid flickering
+        let mut frame = String::from("\x1b[H");
+        frame.push_str(&render_pixels(self.state.pixels(), self.style));
+        frame.push('\n');
+
+        let chars = self.state.chars();
+        let has_text = (0..chars.height())
+            .any(|y| (0..chars.width()).any(|x| chars.get(x, y) != 0));
+        if has_text {
+            frame.push_str(&chars.to_string());
+            frame.push('\n');
+        }
+
+        // clear everything below, e.g. text from before a reset
+        frame.push_str("\x1b[J");
+        self.writer.write_all(frame.as_bytes())?;
+        self.writer.flush()
+    }
+}
+
+#[cfg(feature = "net")]
+impl<W: Write + Send + 'static> TerminalPreview<W> {
+    /// Shows every packet sent through the connection from now on.
+    ///
+    /// Errors while drawing are logged, sending is not affected by them.
+    pub fn attach(self, connection: &mut Connection) {
+        let preview = Mutex::new(self);
+        connection.set_tap(move |packet| {
+            let mut preview =
+                preview.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Err(err) = preview.show_packet(packet) {
+                warn!("could not draw preview: {err}");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        render_pixels, ByteGrid, Command, CompressionCode, Grid, Origin,
+        Packet, PixelGrid, TerminalPreview, TerminalStyle,
+    };
+
+    #[test]
+    fn half_blocks() {
+        let mut pixels = PixelGrid::new(8, 3);
+        pixels.set(0, 0, true);
+        pixels.set(0, 1, true);
+        pixels.set(2, 2, true);
+        assert_eq!(
+            render_pixels(&pixels, TerminalStyle::HalfBlocks),
+            "█       \n  ▀     "
+        );
+    }
+
+    #[test]
+    fn braille() {
+        let mut pixels = PixelGrid::new(8, 4);
+        pixels.set(0, 0, true);
+        pixels.set(1, 3, true);
+        pixels.set(7, 0, true);
+        pixels.set(7, 1, true);
+        pixels.set(7, 2, true);
+        pixels.set(7, 3, true);
+        assert_eq!(render_pixels(&pixels, TerminalStyle::Braille), "⢁⠀⠀⢸");
+    }
+
+    #[test]
+    fn preview_draws_commands() {
+        let mut preview = TerminalPreview::new(vec![]);
+        preview.set_style(TerminalStyle::Braille);
+        let mut pixels = PixelGrid::new(8, 4);
+        pixels.fill(true);
+
+        let command = Command::BitmapLinearWin(
+            Origin(0, 0),
+            pixels,
+            CompressionCode::Uncompressed,
+        );
+        preview.show_packet(&Packet::from(command)).unwrap();
+        assert!(preview.state().pixels().get(7, 3));
+
+        let output = String::from_utf8(preview.writer.clone()).unwrap();
+        assert!(output.starts_with("\x1b[H⣿⣿⣿⣿⠀"));
+        // 40 lines of pixels and the clearing of the rest
+        assert_eq!(output.lines().count(), 41);
+    }
+
+    #[test]
+    fn preview_draws_text() {
+        let mut preview = TerminalPreview::new(vec![]);
+        let text = ByteGrid::load(3, 1, &[b'h', b'i', 0x01]);
+        preview
+            .show(&Command::Cp437Data(Origin(1, 0), text))
+            .unwrap();
+
+        let output = String::from_utf8(preview.writer.clone()).unwrap();
+        assert!(output.contains("\n hi☺ "));
+    }
+}
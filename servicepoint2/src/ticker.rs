@@ -0,0 +1,194 @@
+#[cfg(feature = "net")]
+use std::thread;
+
+use crate::{
+    draw_text, text_width, Command, CompressionCode, Grid, Origin, PixelGrid,
+    TILE_SIZE,
+};
+#[cfg(feature = "net")]
+use crate::{Connection, FRAME_PACING};
+
+/// Text scrolling from right to left through a strip of the screen, one tile row high.
+///
+/// The text enters on the right edge and moves left until it has left the strip completely.
+/// After that, it starts over or stops, depending on `set_repeat`.
+///
+/// Every frame is a `Command::BitmapLinearWin` covering only the strip, so other parts of the
+/// screen can be used for something else.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "net", doc = "```rust")]
+#[cfg_attr(not(feature = "net"), doc = "```rust,ignore")]
+/// use servicepoint2::{Connection, Origin, Ticker};
+///
+/// let connection = Connection::open("172.23.42.29:2342").unwrap();
+/// let mut ticker = Ticker::new(Origin(0, 152), 64, "News");
+/// ticker.set_speed(8);
+/// ticker.set_repeat(false);
+/// ticker.run(&connection).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Ticker {
+    origin: Origin,
+    width: usize,
+    text: String,
+    speed: usize,
+    repeat: bool,
+    compression: CompressionCode,
+    /// how many pixels the text moved since entering the strip
+    position: usize,
+}
+
+impl Ticker {
+    /// Creates a looping ticker moving by one pixel per frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin`: position of the top left pixel of the strip on screen
+    /// * `width`: width of the strip in pixels
+    /// * `text`: the text to show
+    ///
+    /// # Panics
+    ///
+    /// - when the x-coordinate of the origin or the width is not dividable by 8
+    #[must_use]
+    pub fn new(origin: Origin, width: usize, text: &str) -> Self {
+        assert_eq!(origin.0 % TILE_SIZE, 0, "origin has to be tile aligned");
+        assert_eq!(width % TILE_SIZE, 0, "width has to be tile aligned");
+        Self {
+            origin,
+            width,
+            text: text.to_string(),
+            speed: 1,
+            repeat: true,
+            compression: CompressionCode::Uncompressed,
+            position: 0,
+        }
+    }
+
+    /// Sets how many pixels the text moves per frame.
+    pub fn set_speed(&mut self, pixels_per_frame: usize) {
+        self.speed = pixels_per_frame;
+    }
+
+    /// Sets whether the text starts over after leaving the strip.
+    pub fn set_repeat(&mut self, repeat: bool) {
+        self.repeat = repeat;
+    }
+
+    /// Sets the compression used for sending frames.
+    pub fn set_compression(&mut self, compression: CompressionCode) {
+        self.compression = compression;
+    }
+
+    /// Replaces the text and starts scrolling from the right edge again.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.position = 0;
+    }
+
+    /// Whether the text has left the strip and will not start over
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.position > self.scroll_length()
+    }
+
+    /// Draws the current frame of the strip.
+    #[must_use]
+    pub fn render(&self) -> PixelGrid {
+        let mut strip = PixelGrid::new(self.width, TILE_SIZE);
+        let x = self.width as isize - self.position as isize;
+        draw_text(&mut strip, x, 0, &self.text);
+        strip
+    }
+
+    /// Moves the text to the next frame.
+    pub fn advance(&mut self) {
+        if self.is_finished() {
+            return;
+        }
+        self.position += self.speed;
+        if self.is_finished() && self.repeat {
+            self.position = 0;
+        }
+    }
+
+    /// Sends frames to the display, waiting `FRAME_PACING` between them, until the ticker is
+    /// finished. With repeat enabled, this only returns on errors.
+    ///
+    /// # Errors
+    ///
+    /// Any errors produced while sending.
+    #[cfg(feature = "net")]
+    pub fn run(&mut self, connection: &Connection) -> std::io::Result<()> {
+        for command in self {
+            connection.send(command.into())?;
+            thread::sleep(FRAME_PACING);
+        }
+        Ok(())
+    }
+
+    /// Pixels the text moves from entering on the right until it has completely left on the
+    /// left.
+    fn scroll_length(&self) -> usize {
+        self.width + text_width(&self.text)
+    }
+}
+
+impl Iterator for Ticker {
+    type Item = Command;
+
+    /// Renders the current frame into a command and moves on to the next frame.
+    ///
+    /// returns: `None` when the ticker is finished
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_finished() {
+            return None;
+        }
+        let command = Command::BitmapLinearWin(
+            self.origin,
+            self.render(),
+            self.compression,
+        );
+        self.advance();
+        Some(command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Command, Grid, Origin, Ticker};
+
+    #[test]
+    fn scrolls_through() {
+        let mut ticker = Ticker::new(Origin(8, 16), 16, "T");
+        ticker.set_repeat(false);
+        ticker.set_speed(2);
+
+        let first = ticker.next().unwrap();
+        let Command::BitmapLinearWin(Origin(8, 16), pixels, _) = first else {
+            panic!("unexpected command {first:?}");
+        };
+        assert_eq!((pixels.width(), pixels.height()), (16, 8));
+        assert!(!pixels.get(15, 0));
+
+        let second = ticker.render();
+        assert!(second.get(14, 0));
+        assert!(second.get(15, 0));
+
+        // 16 pixels of strip plus 6 of text, two per frame, one already taken
+        assert_eq!(ticker.count(), 11);
+    }
+
+    #[test]
+    fn repeats() {
+        let mut ticker = Ticker::new(Origin(0, 0), 8, "ab");
+        let first = ticker.render();
+        for _ in 0..=(8 + 12) {
+            ticker.advance();
+        }
+        assert!(!ticker.is_finished());
+        assert_eq!(ticker.render(), first);
+    }
+}
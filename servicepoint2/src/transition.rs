@@ -0,0 +1,388 @@
+use crate::random::XorShift;
+use crate::{
+    Area, Brightness, ByteGrid, Command, CompressionCode, Grid, Origin,
+    PixelGrid,
+};
+
+/// A direction on screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// towards the left edge
+    Left,
+    /// towards the right edge
+    Right,
+    /// towards the top edge
+    Up,
+    /// towards the bottom edge
+    Down,
+}
+
+/// How a `Transition` gets from one image to the other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionEffect {
+    /// an edge moving in the direction reveals the new image
+    Wipe(Direction),
+    /// the pixels change to the new image one by one in a random order.
+    /// The same seed always results in the same order.
+    Dissolve(u64),
+    /// the new image pushes the old one out of the screen, moving in the direction
+    Slide(Direction),
+    /// the brightness goes down to zero, the image changes and the brightness goes back up
+    /// to the specified brightness
+    Fade(Brightness),
+}
+
+/// An animation from one `PixelGrid` to another.
+///
+/// Iterating yields one `Command` per frame, the last one leaving the new image on screen.
+/// The frames can also be rendered directly, e.g. for use with an `Animator`.
+///
+/// # Examples
+///
+#[cfg_attr(feature = "net", doc = "```rust")]
+#[cfg_attr(not(feature = "net"), doc = "```rust,ignore")]
+/// use std::thread;
+/// use servicepoint2::{
+///     Connection, Direction, Grid, PixelGrid, Transition, TransitionEffect, FRAME_PACING,
+/// };
+///
+/// let connection = Connection::open("172.23.42.29:2342").unwrap();
+/// let old = PixelGrid::max_sized();
+/// let mut new = PixelGrid::max_sized();
+/// new.fill(true);
+///
+/// let wipe = TransitionEffect::Wipe(Direction::Right);
+/// for command in Transition::new(old, new, wipe, 10) {
+///     connection.send(command.into()).unwrap();
+///     thread::sleep(FRAME_PACING);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Transition {
+    from: PixelGrid,
+    to: PixelGrid,
+    effect: TransitionEffect,
+    frames: usize,
+    origin: Origin,
+    compression: CompressionCode,
+    /// the pixel indexes in the order they change in, only used for dissolving
+    order: Vec<usize>,
+    /// the next frame to yield when iterating, starting at 1
+    next_frame: usize,
+}
+
+impl Transition {
+    /// Creates a transition shown at the top left of the screen.
+    ///
+    /// # Arguments
+    ///
+    /// * `from`: the image at the start
+    /// * `to`: the image at the end
+    /// * `effect`: how to get from one to the other
+    /// * `frames`: the number of frames the transition takes
+    ///
+    /// # Panics
+    ///
+    /// - when the images do not have the same size
+    /// - when there are no frames, or less than three for fading
+    #[must_use]
+    pub fn new(
+        from: PixelGrid,
+        to: PixelGrid,
+        effect: TransitionEffect,
+        frames: usize,
+    ) -> Self {
+        assert_eq!(
+            (from.width(), from.height()),
+            (to.width(), to.height()),
+            "images have to be the same size"
+        );
+        assert!(frames > 0, "a transition needs at least one frame");
+        if let TransitionEffect::Fade(_) = effect {
+            assert!(frames >= 3, "fading needs at least three frames");
+        }
+
+        let order = match effect {
+            TransitionEffect::Dissolve(seed) => {
+                shuffled(from.width() * from.height(), seed)
+            }
+            _ => vec![],
+        };
+        Self {
+            from,
+            to,
+            effect,
+            frames,
+            origin: Origin(0, 0),
+            compression: CompressionCode::Uncompressed,
+            order,
+            next_frame: 1,
+        }
+    }
+
+    /// Sets where on screen the transition is shown.
+    /// The x-coordinate has to be dividable by 8.
+    pub fn set_origin(&mut self, origin: Origin) {
+        self.origin = origin;
+    }
+
+    /// Sets the compression used for sending frames.
+    pub fn set_compression(&mut self, compression: CompressionCode) {
+        self.compression = compression;
+    }
+
+    /// The number of frames the transition takes
+    #[must_use]
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Renders the pixels of the frame, where frame 0 is the old image and the last frame is
+    /// the new image.
+    ///
+    /// For `TransitionEffect::Fade`, the image changes in the middle.
+    #[must_use]
+    pub fn render(&self, frame: usize) -> PixelGrid {
+        let frame = frame.min(self.frames);
+        let (width, height) = (self.from.width(), self.from.height());
+        // how much of the new image is visible in the direction of the effect
+        let progress = |size: usize| size * frame / self.frames;
+
+        let mut result = PixelGrid::new(width, height);
+        match self.effect {
+            TransitionEffect::Wipe(direction) => {
+                for y in 0..height {
+                    for x in 0..width {
+                        let new = match direction {
+                            Direction::Right => x < progress(width),
+                            Direction::Left => x >= width - progress(width),
+                            Direction::Down => y < progress(height),
+                            Direction::Up => y >= height - progress(height),
+                        };
+                        let source = if new { &self.to } else { &self.from };
+                        result.set(x, y, source.get(x, y));
+                    }
+                }
+            }
+            TransitionEffect::Slide(direction) => {
+                for y in 0..height {
+                    for x in 0..width {
+                        result.set(
+                            x,
+                            y,
+                            self.slide_pixel(direction, x, y, frame),
+                        );
+                    }
+                }
+            }
+            TransitionEffect::Dissolve(_) => {
+                result = self.from.clone();
+                for index in &self.order[..progress(self.order.len())] {
+                    let (x, y) = (index % width, index / width);
+                    result.set(x, y, self.to.get(x, y));
+                }
+            }
+            TransitionEffect::Fade(_) => {
+                let source = if frame < self.fade_swap_frame() {
+                    &self.from
+                } else {
+                    &self.to
+                };
+                result = source.clone();
+            }
+        }
+        result
+    }
+
+    /// Gets the pixel at the position of a sliding frame.
+    fn slide_pixel(
+        &self,
+        direction: Direction,
+        x: usize,
+        y: usize,
+        frame: usize,
+    ) -> bool {
+        let (width, height) = (self.from.width(), self.from.height());
+        let dx = width * frame / self.frames;
+        let dy = height * frame / self.frames;
+        match direction {
+            // content moves left, so the new image comes in from the right
+            Direction::Left if x + dx < width => self.from.get(x + dx, y),
+            Direction::Left => self.to.get(x + dx - width, y),
+            Direction::Right if x >= dx => self.from.get(x - dx, y),
+            Direction::Right => self.to.get(x + width - dx, y),
+            Direction::Up if y + dy < height => self.from.get(x, y + dy),
+            Direction::Up => self.to.get(x, y + dy - height),
+            Direction::Down if y >= dy => self.from.get(x, y - dy),
+            Direction::Down => self.to.get(x, y + height - dy),
+        }
+    }
+
+    /// The frame in which the image changes while fading
+    fn fade_swap_frame(&self) -> usize {
+        self.frames / 2 + 1
+    }
+
+    /// The command for showing the frame on the display.
+    ///
+    /// While fading, only the frame in the middle changes the pixels, the others change the
+    /// brightness. The brightness of the whole screen is used if the images cover it,
+    /// otherwise only the brightness of the covered tiles.
+    #[must_use]
+    pub fn command(&self, frame: usize) -> Command {
+        let pixels_command = |pixels| {
+            Command::BitmapLinearWin(self.origin, pixels, self.compression)
+        };
+        let TransitionEffect::Fade(max) = self.effect else {
+            return pixels_command(self.render(frame));
+        };
+
+        let swap = self.fade_swap_frame();
+        let frame = frame.min(self.frames);
+        let brightness = match frame {
+            frame if frame == swap => {
+                return pixels_command(self.to.clone());
+            }
+            frame if frame < swap => {
+                let steps = swap - 1;
+                max.interpolate(Brightness::MIN, frame as f64 / steps as f64)
+            }
+            frame => {
+                let steps = self.frames - swap;
+                Brightness::MIN
+                    .interpolate(max, (frame - swap) as f64 / steps as f64)
+            }
+        };
+
+        let area = Area::of_command(&pixels_command(PixelGrid::new(
+            self.from.width(),
+            self.from.height(),
+        )));
+        if area == Area::FULL_SCREEN {
+            return Command::Brightness(brightness);
+        }
+        let mut grid = ByteGrid::new(area.width, area.height);
+        grid.fill(brightness.into());
+        Command::CharBrightness(Origin(area.x, area.y), grid)
+    }
+}
+
+impl Iterator for Transition {
+    type Item = Command;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next_frame > self.frames {
+            return None;
+        }
+        let command = self.command(self.next_frame);
+        self.next_frame += 1;
+        Some(command)
+    }
+}
+
+/// The numbers `0..count` in a random order
+fn shuffled(count: usize, seed: u64) -> Vec<usize> {
+    let mut random = XorShift::new(seed);
+    let mut order: Vec<usize> = (0..count).collect();
+    for index in (1..count).rev() {
+        let other = random.below(index as u64 + 1) as usize;
+        order.swap(index, other);
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Brightness, ByteGrid, Command, Direction, Grid, Origin, PixelGrid,
+        Transition, TransitionEffect,
+    };
+
+    fn images() -> (PixelGrid, PixelGrid) {
+        let from = PixelGrid::new(16, 4);
+        let mut to = PixelGrid::new(16, 4);
+        to.fill(true);
+        (from, to)
+    }
+
+    #[test]
+    fn wipe() {
+        let (from, to) = images();
+        let transition = Transition::new(
+            from.clone(),
+            to.clone(),
+            TransitionEffect::Wipe(Direction::Left),
+            4,
+        );
+        assert_eq!(transition.render(0), from);
+        let half = transition.render(2);
+        assert!(!half.get(7, 0));
+        assert!(half.get(8, 3));
+        assert_eq!(transition.render(4), to);
+    }
+
+    #[test]
+    fn slide() {
+        let (from, mut to) = images();
+        to.set(0, 3, false);
+        let transition = Transition::new(
+            from,
+            to.clone(),
+            TransitionEffect::Slide(Direction::Down),
+            4,
+        );
+        // the new image enters from the top, bottom row first
+        let first = transition.render(1);
+        assert!(!first.get(0, 0));
+        assert!(first.get(1, 0));
+        assert!(!first.get(1, 1));
+        assert_eq!(transition.render(4), to);
+    }
+
+    #[test]
+    fn dissolve() {
+        let (from, to) = images();
+        let transition = Transition::new(
+            from,
+            to.clone(),
+            TransitionEffect::Dissolve(42),
+            8,
+        );
+        let counts: Vec<usize> = (0..=8)
+            .map(|frame| transition.render(frame).count_ones())
+            .collect();
+        assert_eq!(counts, vec![0, 8, 16, 24, 32, 40, 48, 56, 64]);
+
+        let commands: Vec<Command> = transition.collect();
+        assert_eq!(commands.len(), 8);
+        assert!(matches!(
+            commands.last(),
+            Some(Command::BitmapLinearWin(Origin(0, 0), pixels, _))
+                if *pixels == to
+        ));
+    }
+
+    #[test]
+    fn fade() {
+        let (from, to) = images();
+        let max = Brightness::saturating_from(10);
+        let mut transition =
+            Transition::new(from, to.clone(), TransitionEffect::Fade(max), 5);
+        transition.set_origin(Origin(8, 8));
+
+        let fill = |value| {
+            let mut grid = ByteGrid::new(2, 1);
+            grid.fill(value);
+            Command::CharBrightness(Origin(1, 1), grid)
+        };
+        let commands: Vec<Command> = transition.collect();
+        assert_eq!(commands[0], fill(5));
+        assert_eq!(commands[1], fill(0));
+        assert!(matches!(
+            &commands[2],
+            Command::BitmapLinearWin(Origin(8, 8), pixels, _) if *pixels == to
+        ));
+        assert_eq!(commands[3], fill(5));
+        assert_eq!(commands[4], fill(10));
+    }
+}